@@ -11,4 +11,6 @@ cargo run --bin mapgen-cli -- --config examples/archipelago.toml --output output
 cargo run --bin mapgen-cli -- --config examples/mediterranean.toml --output output/med
 
 # Ледниковый период
-cargo run --bin mapgen-cli -- --config examples/ice_age_earth.toml --output output/ice_age
\ No newline at end of file
+cargo run --bin mapgen-cli -- --config examples/ice_age_earth.toml --output output/ice_age
+# Пользовательский пресет (две крупные материковые плиты)
+cargo run --bin mapgen-cli -- --config examples/twin_continents.toml --output output/twin
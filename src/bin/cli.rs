@@ -6,7 +6,7 @@
 //!
 //! ## Конвейер генерации
 //!
-//! 1. **Загрузка конфигурации** — чтение параметров из TOML-файла
+//! 1. **Загрузка конфигурации** — чтение параметров из TOML-файла и их проверка
 //! 2. **Генерация карты высот** — процедурная генерация рельефа с эрозией
 //! 3. **Климат и биомы** — расчёт температуры, влажности и назначение биомов
 //! 4. **Классификация воды** — разделение на океаны и озёра
@@ -22,6 +22,21 @@
 //! # Генерация мира из конфигурации
 //! cargo run --release -- --config world.toml --output output/
 //!
+//! # Переопределение отдельных полей без правки файла
+//! cargo run --release -- --config world.toml --set seed=7 --set terrain.total_provinces=200
+//!
+//! # Пакетная генерация: каждый сид в свой подкаталог + сводка index.json
+//! cargo run --release -- --config world.toml --seeds 1..50 --output output/sweep
+//!
+//! # Только данные провинций, без изображений (для сборочных серверов)
+//! cargo run --release -- --config world.toml --only provinces --no-images
+//!
+//! # Перегенерация поверх отредактированной вручную карты высот
+//! cargo run --release -- --config world.toml --from-heightmap output/heightmap.png
+//!
+//! # Дополнительно сохранить единый файл мира world.json
+//! cargo run --release -- --config world.toml --bundle
+//!
 //! # Пример конфигурации (world.toml)
 //! seed = 42
 //! width = 2048
@@ -33,38 +48,40 @@
 //! ```
 //!
 //! ## Выходные файлы
-//! - `heightmap.png` — карта высот (градации серого)
+//! - `heightmap.png` — карта высот (16-битные градации серого)
+//! - `heightmap.r32` + `heightmap.json` — карта высот без потери точности
+//!   (сырые `f32` little-endian и описание: размеры, уровень моря)
 //! - `normals.png` — карта нормалей для шейдинга
+//! - `temperature.png`, `humidity.png` — климатические карты (16-битные градации серого)
+//! - `temperature.r32`, `humidity.r32` (+ `.json`) — климатические карты без потери
+//!   точности (тот же формат, что у `heightmap.r32`)
 //! - `biomes.png` — карта биомов (цветовая схема)
 //! - `rivers.png` — гидрографическая сеть
 //! - `provinces.png` — административное деление на провинции
 //! - `regions.png` — группировка провинций в регионы
-//! - `provinces.json` — данные провинций (геометрия, биомы, типы)
+//! - `provinces.json` — данные провинций (геометрия, биомы, типы, соседи)
+//! - `adjacency.json` — граф смежности провинций (сухопутные границы, побережья,
+//!   морские пути с длиной границы и стоимостью перемещения)
 //! - `regions.json` — данные регионов (состав провинций, цвета)
+//! - `strategic.json` — стратегические точки (тип, провинция, пиксельная позиция)
+//! - `sea_lanes.json` — сеть морских путей: акватории, пути между ними (расстояние,
+//!   стоимость, глубина, блокировка льдом) и маршруты между портами
+//! - `world.json` — единый файл мира со всеми слоями (только с `--bundle`,
+//!   см. `mapgen::bundle`)
+//! - `index.json` — сводка серии при пакетной генерации (`--seeds`)
 
 use clap::Parser;
 use mapgen::{
-    WorldGenerationParams,
-    biome::assign_biomes,
-    climate::{calculate_humidity, generate_climate_maps},
-    generate_heightmap,
-    province::{
-        generator::{generate_province_seeds, generate_provinces_from_seeds},
-        graph::build_province_graph_with_map,
-        merge::merge_small_provinces,
-        png::ProvinceMap,
-        water::{WaterType, classify_water},
-    },
-    region::{group_provinces_into_regions, png::RegionMap},
-    rivers::generate_rivers,
-    strategic::find_strategic_points,
+    AreaReport, MapgenError, PartialWorld, Stage, ValidationIssue, WorldGenerationParams,
+    build_sea_lanes, config::layers::add_override, generate_partial_world,
+    generate_partial_world_from_heightmap, heightmap::Heightmap, province::graph::BorderKind,
+    province::graph::adjacency_list, province::graph::neighbor_ids, province::index::ProvinceIndex,
+    province::png::ProvinceMap, region::png::RegionMap, strategic::StrategicPoint,
 };
 use serde::Serialize;
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::PathBuf;
-
-// Уровень моря (фиксированный для всех миров)
-const SEA_LEVEL: f32 = 0.5;
+use std::path::{Path, PathBuf};
 
 /// Параметры командной строки генератора карт
 #[derive(Parser, Debug)]
@@ -91,6 +108,109 @@ struct Cli {
     /// По умолчанию: `./output`
     #[arg(short, long, default_value = "output", value_name = "DIR")]
     output: PathBuf,
+
+    /// Переопределение поля конфигурации (можно указывать несколько раз)
+    ///
+    /// Составные ключи адресуют вложенные секции: `--set terrain.total_provinces=200`.
+    /// Переопределения применяются поверх файла конфигурации.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
+
+    /// Серия сидов для пакетной генерации: диапазон `1..50` (включительно) или список `1,7,42`
+    ///
+    /// Каждый мир сохраняется в подкаталог `seed_<сид>`, а сводка — в `index.json`.
+    #[arg(long, value_name = "RANGE", value_parser = parse_seeds)]
+    seeds: Option<SeedList>,
+
+    /// Сохранить только указанные этапы (через запятую):
+    /// heightmap, climate, biomes, rivers, provinces, regions, strategic
+    ///
+    /// Необходимые вышестоящие этапы вычисляются автоматически, но не сохраняются.
+    #[arg(long, value_delimiter = ',', value_name = "STAGES")]
+    only: Vec<Stage>,
+
+    /// Не сохранять указанные этапы (через запятую); применяется после `--only`
+    #[arg(long, value_delimiter = ',', value_name = "STAGES")]
+    skip: Vec<Stage>,
+
+    /// Не сохранять PNG-изображения (только данные: JSON и файлы `.r32`)
+    #[arg(long)]
+    no_images: bool,
+
+    /// Сохранить единый файл мира `world.json`
+    ///
+    /// Файл содержит все попиксельные слои мира и на больших картах занимает
+    /// сотни мегабайт, поэтому записывается только по запросу. Требует полной
+    /// генерации: несовместим с `--only`/`--skip`.
+    #[arg(long, conflicts_with_all = ["only", "skip"])]
+    bundle: bool,
+
+    /// Готовая карта высот: 8/16-бит PNG, вещественное изображение или `heightmap.r32`
+    ///
+    /// Для `.r32`/`.raw` размеры и уровень моря берутся из JSON-описания рядом с файлом.
+    ///
+    /// Генерация шума и эрозия пропускаются: климат, биомы, реки и провинции
+    /// строятся на переданном рельефе. Размеры мира берутся из изображения.
+    #[arg(long, value_name = "FILE")]
+    from_heightmap: Option<PathBuf>,
+}
+
+/// Список сидов для пакетной генерации
+#[derive(Debug, Clone)]
+struct SeedList(Vec<u64>);
+
+/// Разбирает серию сидов: диапазон `a..b` (включительно) или список через запятую
+fn parse_seeds(value: &str) -> Result<SeedList, String> {
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<u64>()
+            .map_err(|e| format!("некорректный сид «{s}»: {e}"))
+    };
+
+    let seeds = if let Some((start, end)) = value.split_once("..") {
+        let (start, end) = (parse(start)?, parse(end.trim_start_matches('='))?);
+        if start > end {
+            return Err(format!("пустой диапазон сидов {start}..{end}"));
+        }
+        (start..=end).collect()
+    } else {
+        value.split(',').map(parse).collect::<Result<Vec<_>, _>>()?
+    };
+    Ok(SeedList(seeds))
+}
+
+/// Запись сводного индекса пакетной генерации (`index.json`)
+#[derive(Serialize)]
+struct SweepEntry {
+    /// Сид мира
+    seed: u64,
+
+    /// Подкаталог с результатами относительно каталога вывода
+    directory: String,
+
+    /// Количество провинций (`null`, если этап не выполнялся)
+    provinces: Option<usize>,
+
+    /// Количество регионов (`null`, если этап не выполнялся)
+    regions: Option<usize>,
+
+    /// Количество стратегических точек (`null`, если этап не выполнялся)
+    strategic_points: Option<usize>,
+
+    /// Фактическая доля суши (0.0–1.0)
+    land_ratio: Option<f32>,
+}
+
+/// Настройки экспорта: какие этапы сохранять и нужны ли изображения
+struct ExportOptions {
+    /// Этапы, результаты которых сохраняются (зависимости вычисляются, но не сохраняются)
+    stages: BTreeSet<Stage>,
+
+    /// Сохранять ли PNG-изображения
+    images: bool,
+
+    /// Сохранять ли единый файл мира `world.json` (`--bundle`)
+    bundle: bool,
 }
 
 /// Сериализуемая версия провинции для экспорта в JSON
@@ -148,6 +268,12 @@ struct SerializableProvince {
     /// }
     /// ```
     biomes: std::collections::HashMap<String, f32>,
+
+    /// Соседние провинции (`province_id`, по возрастанию)
+    ///
+    /// Включает сухопутных соседей, побережья и морские пути; атрибуты
+    /// границ (длина, тип, стоимость перемещения) — в `adjacency.json`.
+    neighbors: Vec<u32>,
 }
 
 /// Сериализуемая версия региона для экспорта в JSON
@@ -173,27 +299,16 @@ struct SerializableRegion {
 
 /// Основная функция CLI-генератора
 ///
-/// Реализует полный конвейер генерации мира от карты высот до экспорта данных.
+/// Загружает конфигурацию с переопределениями `--set`, проверяет её и запускает
+/// генерацию одного мира или серии миров (`--seeds`).
 /// Возвращает `Ok(())` при успешной генерации или ошибку при сбое.
 ///
-/// # Этапы генерации
-/// 1. Загрузка конфигурации из TOML
-/// 2. Генерация карты высот с эрозией
-/// 3. Расчёт климата (температура, влажность, ветры)
-/// 4. Назначение биомов на основе климата и высоты
-/// 5. Классификация водных поверхностей (океаны vs озёра)
-/// 6. Генерация гидрографической сети (реки)
-/// 7. Разбиение мира на провинции (семена + рост)
-/// 8. Слияние мелких провинций для улучшения геймплея
-/// 9. Группировка провинций в регионы
-/// 10. Поиск стратегических точек (порты, устья, перевалы)
-/// 11. Экспорт всех результатов в изображения и JSON
-///
 /// # Пример вызова
 /// ```bash
 /// cargo run -- --config world.toml --output output/
+/// cargo run -- --config world.toml --set seed=7 --set terrain.total_provinces=200
+/// cargo run -- --config world.toml --seeds 1..50 --output output/sweep
 /// ```
-#[allow(clippy::too_many_lines)] // CLI-бинарник допускает длинную функцию main()
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // === ЭТАП 0: Парсинг аргументов командной строки ===
     let cli = Cli::parse();
@@ -204,250 +319,464 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // === ЭТАП 1: Загрузка конфигурации ===
     println!("🔍 Загрузка конфигурации из {}...", cli.config.display());
-    let params = WorldGenerationParams::from_toml_file(cli.config.to_str().unwrap())?;
+    let mut overrides = toml::Table::new();
+    for assignment in &cli.overrides {
+        add_override(&mut overrides, assignment)?;
+    }
+    if !overrides.is_empty() {
+        println!("🔧 Переопределения: {}", cli.overrides.join(", "));
+    }
+    let mut params = WorldGenerationParams::from_toml_file_with_overrides(
+        cli.config.to_str().unwrap(),
+        &overrides,
+    )?;
+
+    // Готовая карта высот задаёт размеры мира
+    let source_heightmap = cli
+        .from_heightmap
+        .as_deref()
+        .map(|path| load_source_heightmap(path, &mut params))
+        .transpose()?;
     println!(
         "⚙️  Сид: {}, Тип мира: {:?}, Размер: {}×{}",
         params.seed, params.world_type, params.width, params.height
     );
 
-    // === ЭТАП 2: Генерация карты высот ===
-    println!(
-        "🌍 Генерация карты высот (размер: {}×{})...",
-        params.width, params.height
-    );
-    let terrain = if params.terrain == mapgen::config::TerrainSettings::default() {
-        params.world_type.default_terrain()
+    // Проверка параметров до запуска генерации
+    let (errors, warnings): (Vec<_>, Vec<_>) = params
+        .validate()
+        .into_iter()
+        .partition(ValidationIssue::is_error);
+    for warning in &warnings {
+        println!("⚠️  {}: {}", warning.field, warning.message);
+    }
+    for error in &errors {
+        eprintln!("❌ {}: {}", error.field, error.message);
+    }
+    if !errors.is_empty() {
+        return Err(MapgenError::InvalidConfig { issues: errors }.into());
+    }
+
+    // Выбор этапов: --only сужает набор, --skip исключает этапы из вывода
+    let mut stages: BTreeSet<Stage> = if cli.only.is_empty() {
+        Stage::ALL.into_iter().collect()
     } else {
-        params.terrain.clone()
+        cli.only.iter().copied().collect()
+    };
+    for stage in &cli.skip {
+        stages.remove(stage);
+    }
+    let export = ExportOptions {
+        stages,
+        images: !cli.no_images,
+        bundle: cli.bundle,
     };
 
-    let heightmap = generate_heightmap(
-        params.seed,
-        params.width,
-        params.height,
-        params.world_type,
-        params.islands.island_density,
-        &terrain,
-    );
-    println!("✅ Карта высот сгенерирована");
-    println!("Сохраняем карту высот в PNG...");
-    heightmap.save_as_png(cli.output.join("heightmap.png").to_str().unwrap())?;
-    println!("✅ Карта высот сохранена");
-
-    // === ЭТАП 3: Генерация климата ===
-    println!("🌡️  Генерация климата...");
-    let (temperature, winds) = generate_climate_maps(
-        params.seed,
-        params.width,
-        params.height,
-        &heightmap.data,
-        params.climate.global_temperature_offset,
-        params.climate.polar_amplification,
-        params.climate.climate_latitude_exponent,
-        SEA_LEVEL,
-    );
+    let Some(SeedList(seeds)) = cli.seeds else {
+        generate_and_export(&params, source_heightmap, &cli.output, &export)?;
+        return Ok(());
+    };
+
+    // === Пакетная генерация по серии сидов ===
+    println!("🎲 Пакетная генерация: {} сидов", seeds.len());
+    let mut index = Vec::with_capacity(seeds.len());
+    for (i, &seed) in seeds.iter().enumerate() {
+        println!("\n🎲 [{}/{}] Сид {seed}", i + 1, seeds.len());
+        let directory = format!("seed_{seed}");
+        let output = cli.output.join(&directory);
+        fs::create_dir_all(&output)?;
+
+        let world = generate_and_export(
+            &WorldGenerationParams {
+                seed,
+                ..params.clone()
+            },
+            source_heightmap.clone(),
+            &output,
+            &export,
+        )?;
+        index.push(SweepEntry {
+            seed,
+            directory,
+            provinces: world.provinces.as_ref().map(Vec::len),
+            regions: world.regions.as_ref().map(Vec::len),
+            strategic_points: world.strategic_points.as_ref().map(Vec::len),
+            land_ratio: world.land_ratio(),
+        });
+    }
+
+    fs::write(
+        cli.output.join("index.json"),
+        serde_json::to_string_pretty(&index)?,
+    )?;
+    println!("\n📇 Сводка серии сохранена в index.json:");
+    let count = |value: Option<usize>| value.map_or_else(|| "—".to_string(), |v| v.to_string());
+    for entry in &index {
+        println!(
+            "   • сид {:>6}: {:>4} провинций, {:>3} регионов, {:>4} стратегических точек, суша {:.1}%",
+            entry.seed,
+            count(entry.provinces),
+            count(entry.regions),
+            count(entry.strategic_points),
+            entry.land_ratio.unwrap_or(0.0) * 100.0
+        );
+    }
+
+    Ok(())
+}
+
+/// Загружает готовую карту высот для `--from-heightmap`
+///
+/// Размеры мира в `params` заменяются размерами карты; для сырой карты
+/// (`.r32`/`.raw`) также применяется уровень моря из её JSON-описания.
+fn load_source_heightmap(
+    path: &Path,
+    params: &mut WorldGenerationParams,
+) -> Result<Heightmap, MapgenError> {
+    println!("🗺️  Загрузка карты высот из {}...", path.display());
+    let path_str = path.to_str().unwrap();
+    let is_raw = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("r32") || ext.eq_ignore_ascii_case("raw"));
+    let heightmap = if is_raw {
+        // Сырая карта несёт уровень моря, с которым была сгенерирована
+        let (heightmap, info) = Heightmap::load_raw(path_str)?;
+        params.sea_level = info.sea_level;
+        heightmap
+    } else {
+        Heightmap::load_png(path_str)?
+    };
+    params.width = heightmap.width;
+    params.height = heightmap.height;
+    Ok(heightmap)
+}
 
-    let humidity = calculate_humidity(
+/// Генерирует один мир и экспортирует его в каталог `output`
+///
+/// # Этапы генерации
+/// 1. Конвейер `mapgen::generate_partial_world` для выбранных этапов и их зависимостей
+///    (или `generate_partial_world_from_heightmap`, если передана готовая карта высот)
+/// 2. Экспорт результатов выбранных этапов в изображения и JSON
+///
+/// Готовая карта высот не перезаписывается в `heightmap.png`, чтобы не потерять
+/// точность исходного файла; карта нормалей по ней сохраняется.
+///
+/// # Возвращает
+/// Сгенерированный мир (для сводки пакетной генерации).
+#[allow(clippy::too_many_lines)] // CLI-бинарник допускает длинную функцию экспорта
+fn generate_and_export(
+    params: &WorldGenerationParams,
+    source_heightmap: Option<Heightmap>,
+    output: &Path,
+    export: &ExportOptions,
+) -> Result<PartialWorld, Box<dyn std::error::Error>> {
+    // === ЭТАП 2: Генерация мира ===
+    let requested: Vec<Stage> = export.stages.iter().copied().collect();
+    let computed = Stage::with_dependencies(&requested);
+    println!(
+        "🌍 Генерация мира (размер: {}×{}, этапы: {})...",
         params.width,
         params.height,
-        &heightmap.data,
-        &winds,
-        SEA_LEVEL,
-        params.climate.global_humidity_offset,
+        computed
+            .iter()
+            .map(|s| s.name())
+            .collect::<Vec<_>>()
+            .join(", ")
     );
-    println!("✅ Климат сгенерирован");
+    let from_source = source_heightmap.is_some();
+    let mut world = match source_heightmap {
+        Some(heightmap) => generate_partial_world_from_heightmap(params, heightmap, &requested),
+        None => generate_partial_world(params, &requested),
+    };
+    let exports = |stage: Stage| export.stages.contains(&stage);
+    let images = |stage: Stage| export.images && exports(stage);
+
+    // Сырая карта высот — данные, а не изображение: сохраняется и при --no-images
+    if exports(Stage::Heightmap)
+        && !from_source
+        && let Some(heightmap) = &world.heightmap
+    {
+        println!("Сохраняем карту высот в heightmap.r32...");
+        heightmap.save_raw(
+            output.join("heightmap.r32").to_str().unwrap(),
+            params.sea_level,
+        )?;
+        println!("✅ heightmap.r32 сохранён");
+    }
 
-    // === ЭТАП 4: Назначение биомов ===
-    println!("🌿 Назначение биомов...");
-    let biome_map = assign_biomes(&heightmap, &temperature, &humidity, SEA_LEVEL);
-    println!("✅ Биомы назначены");
+    // Климатические карты: сырые данные всегда, изображения — без --no-images
+    if exports(Stage::Climate)
+        && let (Some(temperature), Some(humidity)) = (&world.temperature, &world.humidity)
+    {
+        for (name, data) in [("temperature", temperature), ("humidity", humidity)] {
+            println!("🌡️  Сохранение климатической карты {name}...");
+            let field = Heightmap {
+                width: params.width,
+                height: params.height,
+                data: data.clone(),
+            };
+            field.save_raw(
+                output.join(format!("{name}.r32")).to_str().unwrap(),
+                params.sea_level,
+            )?;
+            println!("✅ {name}.r32 сохранён");
+            if export.images {
+                field.save_as_png16(output.join(format!("{name}.png")).to_str().unwrap())?;
+                println!("✅ {name}.png сохранён");
+            }
+        }
+    }
 
-    // === ЭТАП 5: Сохранение карты биомов ===
-    println!("🖼️  Сохранение карты биомов...");
-    biome_map.save_as_png(cli.output.join("biomes.png").to_str().unwrap())?;
-    println!("✅ biomes.png сохранён");
+    // === ЭТАП 3: Сохранение изображений ===
+    if images(Stage::Heightmap)
+        && let Some(heightmap) = &world.heightmap
+    {
+        if !from_source {
+            println!("Сохраняем карту высот в 16-битный PNG...");
+            heightmap.save_as_png16(output.join("heightmap.png").to_str().unwrap())?;
+            println!("✅ Карта высот сохранена");
+        }
+
+        // Карта нормалей (для шейдинга в движке)
+        let normals_path = output.join("normals.png");
+        println!(
+            "⛰️  Сохранение карты нормалей в {}...",
+            normals_path.display()
+        );
+        heightmap.save_normals_as_png(normals_path.to_str().unwrap())?;
+        println!("✅ normals.png сохранён");
+    }
 
-    // === ЭТАП 6: Классификация воды и генерация рек ===
-    println!("💧 Классификация водных поверхностей...");
-    let water_type = classify_water(&heightmap, SEA_LEVEL);
-    println!("✅ Вода классифицирована");
+    if images(Stage::Biomes)
+        && let Some(biome_map) = &world.biome_map
+    {
+        println!("🖼️  Сохранение карты биомов...");
+        biome_map.save_as_png(output.join("biomes.png").to_str().unwrap())?;
+        println!("✅ biomes.png сохранён");
+    }
 
-    println!("🌊 Генерация рек...");
-    let river_map = generate_rivers(&heightmap, &biome_map);
-    println!("✅ Реки сгенерированы");
+    if images(Stage::Rivers)
+        && let Some(river_map) = &world.river_map
+    {
+        println!("🖼️  Сохранение карты рек...");
+        river_map.save_as_png(output.join("rivers.png").to_str().unwrap())?;
+        println!("✅ rivers.png сохранён");
+    }
 
-    println!("🖼️  Сохранение карты рек...");
-    river_map.save_as_png(cli.output.join("rivers.png").to_str().unwrap())?;
-    println!("✅ rivers.png сохранён");
+    if images(Stage::Provinces)
+        && let (Some(provinces), Some(pixel_to_id)) = (&world.provinces, &world.pixel_to_id)
+    {
+        println!("🖼️  Сохранение карты провинций...");
+        let province_map = ProvinceMap::from_pixel_map(params.width, params.height, pixel_to_id);
+        province_map.save_as_png(provinces, output.join("provinces.png").to_str().unwrap())?;
+        println!("✅ provinces.png сохранён");
+    }
 
-    // === ЭТАП 7: Сохранение карты нормалей (для шейдинга в движке) ===
-    let normals_path = cli.output.join("normals.png");
-    println!(
-        "⛰️  Сохранение карты нормалей в {}...",
-        normals_path.display()
-    );
-    heightmap.save_normals_as_png(normals_path.to_str().unwrap())?;
-    println!("✅ normals.png сохранён");
-
-    // === ЭТАП 8: Генерация провинций ===
-    println!("🗺️  Генерация провинций...");
-
-    // Расчёт распределения провинций по типу поверхности
-    let land_pixels_count = water_type.iter().filter(|&&t| t == WaterType::Land).count();
-    let total_pixels = (params.width * params.height) as usize;
-    let land_ratio = land_pixels_count as f32 / total_pixels as f32;
-    let total_provinces = terrain.total_provinces;
-
-    // Балансировка: 70% суша / 30% море по умолчанию
-    let land_priority_ratio = 0.7;
-    let mut num_land = (total_provinces as f32 * land_priority_ratio).round() as usize;
-    let mut num_sea = total_provinces - num_land;
-
-    // Защита от деления на ноль
-    if num_land == 0 {
-        num_land = 1;
+    if images(Stage::Regions)
+        && let (Some(regions), Some(pixel_to_id)) = (&world.regions, &world.pixel_to_id)
+    {
+        println!("🖼️  Сохранение карты регионов...");
+        let province_map = ProvinceMap::from_pixel_map(params.width, params.height, pixel_to_id);
+        let index = ProvinceIndex::from_province_map(&province_map);
+        let region_map = RegionMap::from_index(&index, regions);
+        region_map.save_as_png(output.join("regions.png").to_str().unwrap(), regions)?;
+        println!("✅ regions.png сохранён");
     }
-    if num_sea == 0 {
-        num_sea = 1;
+
+    // === ЭТАП 4: Стратегические точки ===
+    if exports(Stage::Strategic)
+        && let Some(strategic_points) = &world.strategic_points
+    {
+        println!(
+            "🎯 Найдено стратегических точек: {} (порты: {}, устья: {}, перевалы: {}, проливы: {})",
+            strategic_points.len(),
+            strategic_points
+                .iter()
+                .filter(|p| matches!(p, StrategicPoint::Port { .. }))
+                .count(),
+            strategic_points
+                .iter()
+                .filter(|p| matches!(p, StrategicPoint::Estuary { .. }))
+                .count(),
+            strategic_points
+                .iter()
+                .filter(|p| matches!(p, StrategicPoint::Pass { .. }))
+                .count(),
+            strategic_points
+                .iter()
+                .filter(|p| matches!(p, StrategicPoint::Strait { .. }))
+                .count()
+        );
+
+        let strategic_json = serde_json::to_string_pretty(strategic_points)?;
+        fs::write(output.join("strategic.json"), strategic_json)?;
+        println!(
+            "✅ strategic.json сохранён ({} точек)",
+            strategic_points.len()
+        );
     }
 
-    // Для водянистых миров (архипелаги) увеличиваем долю морских провинций
-    if land_ratio < 0.3 {
-        num_sea = (total_provinces as f32 * 0.5).round() as usize;
-        num_land = total_provinces - num_sea;
+    if exports(Stage::Strategic)
+        && let (
+            Some(provinces),
+            Some(pixel_to_id),
+            Some(biome_map),
+            Some(water_type),
+            Some(graph),
+            Some(strategic_points),
+        ) = (
+            &world.provinces,
+            &world.pixel_to_id,
+            &world.biome_map,
+            &world.water_type,
+            &world.graph,
+            &world.strategic_points,
+        )
+    {
+        println!("⚓ Построение сети морских путей...");
+        let network = build_sea_lanes(
+            provinces,
+            pixel_to_id,
+            biome_map,
+            water_type,
+            graph,
+            strategic_points,
+        );
+        let sea_lanes_json = serde_json::to_string_pretty(&network)?;
+        fs::write(output.join("sea_lanes.json"), sea_lanes_json)?;
+        println!(
+            "✅ sea_lanes.json сохранён (акваторий: {}, путей: {}, из них во льдах: {}, маршрутов между портами: {})",
+            network.seas.len(),
+            network.lanes.len(),
+            network.lanes.iter().filter(|l| l.ice_blocked).count(),
+            network.routes.len()
+        );
     }
 
-    println!(
-        "📊 Распределение провинций: суша={} ({}%), море={} ({}%)",
-        num_land,
-        (num_land as f32 / total_provinces as f32 * 100.0).round(),
-        num_sea,
-        (num_sea as f32 / total_provinces as f32 * 100.0).round()
-    );
+    // === ЭТАП 5: Экспорт данных в JSON ===
+    if exports(Stage::Provinces)
+        && let Some(provinces) = &world.provinces
+    {
+        println!("📦 Экспорт данных провинций в provinces.json...");
+        let mut neighbors = world.graph.as_ref().map(neighbor_ids).unwrap_or_default();
+        let serializable_provinces: Vec<SerializableProvince> = provinces
+            .iter()
+            .cloned()
+            .map(|p| SerializableProvince {
+                neighbors: neighbors.remove(&p.id).unwrap_or_default(),
+                id: p.id,
+                color: p.color,
+                center: [p.center.0, p.center.1],
+                area: p.area,
+                province_type: p.province_type,
+                coastal: p.coastal,
+                biomes: p.biomes,
+            })
+            .collect();
+
+        let provinces_json = serde_json::to_string_pretty(&serializable_provinces)?;
+        fs::write(output.join("provinces.json"), provinces_json)?;
+        println!(
+            "✅ provinces.json сохранён ({} провинций)",
+            serializable_provinces.len()
+        );
+    }
 
-    // Генерация семян провинций
-    println!("🌱 Генерация семян провинций...");
-    let seeds = generate_province_seeds(
-        &heightmap,
-        &biome_map,
-        &water_type,
-        num_land,
-        num_sea,
-        params.seed,
-    );
-    println!("✅ Семена сгенерированы: {num_land} суша, {num_sea} море");
-
-    // Рост провинций от семян
-    println!("📈 Рост провинций от семян...");
-    let (mut all_provinces, pixel_to_id) =
-        generate_provinces_from_seeds(&heightmap, &biome_map, &water_type, &seeds);
-    println!("✅ Провинции сгенерированы: {}", all_provinces.len());
-
-    // === ЭТАП 9: Слияние мелких провинций ===
-    println!("🔨 Объединение мелких провинций (< 50 пикселей)...");
-    let mut graph =
-        build_province_graph_with_map(&all_provinces, &pixel_to_id, params.width, params.height);
-    merge_small_provinces(&mut all_provinces, &graph);
-    println!("✅ Мелкие провинции объединены");
-
-    // Перестроение графа после слияния
-    graph =
-        build_province_graph_with_map(&all_provinces, &pixel_to_id, params.width, params.height);
-
-    // === ЭТАП 10: Сохранение карты провинций ===
-    println!("🖼️  Сохранение карты провинций...");
-    let province_map = ProvinceMap::from_pixel_map(params.width, params.height, &pixel_to_id);
-    province_map.save_as_png(
-        &all_provinces,
-        cli.output.join("provinces.png").to_str().unwrap(),
-    )?;
-    println!("✅ provinces.png сохранён");
+    if exports(Stage::Provinces)
+        && let Some(graph) = &world.graph
+    {
+        println!("📦 Экспорт графа смежности в adjacency.json...");
+        let edges = adjacency_list(graph);
+        let count = |kind: BorderKind| edges.iter().filter(|e| e.border.kind == kind).count();
+        let (land, coastal, sea) = (
+            count(BorderKind::LandLand),
+            count(BorderKind::LandSea),
+            count(BorderKind::SeaSea),
+        );
+
+        let adjacency_json = serde_json::to_string_pretty(&edges)?;
+        fs::write(output.join("adjacency.json"), adjacency_json)?;
+        println!(
+            "✅ adjacency.json сохранён ({} связей: сухопутных {land}, побережий {coastal}, морских путей {sea})",
+            edges.len()
+        );
+    }
 
-    // === ЭТАП 11: Группировка в регионы ===
-    println!(
-        "🧩 Группировка провинций в регионы (цель: ~{} провинций на регион)...",
-        8
-    );
-    let target_region_size = 8;
-    let regions = group_provinces_into_regions(&all_provinces, &graph, target_region_size);
-    println!("✅ Регионы сформированы: {} регионов", regions.len());
-
-    // === ЭТАП 12: Сохранение карты регионов ===
-    println!("🖼️  Сохранение карты регионов...");
-    let region_map = RegionMap::from_pixel_map(params.width, params.height, &pixel_to_id, &regions);
-    region_map.save_as_png(cli.output.join("regions.png").to_str().unwrap(), &regions)?;
-    println!("✅ regions.png сохранён");
-
-    // === ЭТАП 13: Поиск стратегических точек ===
-    println!("🎯 Поиск стратегических точек...");
-    let strategic_points =
-        find_strategic_points(&all_provinces, &river_map, &biome_map, &pixel_to_id);
-    println!(
-        "✅ Найдено стратегических точек: {} (порты: {}, устья: {}, перевалы: {})",
-        strategic_points.len(),
-        strategic_points
+    if exports(Stage::Regions)
+        && let Some(regions) = &world.regions
+    {
+        println!("📦 Экспорт данных регионов в regions.json...");
+        let serializable_regions: Vec<SerializableRegion> = regions
             .iter()
-            .filter(|p| matches!(p, mapgen::strategic::StrategicPoint::Port { .. }))
-            .count(),
-        strategic_points
-            .iter()
-            .filter(|p| matches!(p, mapgen::strategic::StrategicPoint::Estuary { .. }))
-            .count(),
-        strategic_points
-            .iter()
-            .filter(|p| matches!(p, mapgen::strategic::StrategicPoint::Pass { .. }))
-            .count()
-    );
-
-    // === ЭТАП 14: Экспорт данных в JSON ===
-    println!("📦 Экспорт данных провинций в provinces.json...");
-    let serializable_provinces: Vec<SerializableProvince> = all_provinces
-        .into_iter()
-        .map(|p| SerializableProvince {
-            id: p.id,
-            color: p.color,
-            center: [p.center.0, p.center.1],
-            area: p.area,
-            province_type: p.province_type,
-            coastal: p.coastal,
-            biomes: p.biomes,
-        })
-        .collect();
-
-    let provinces_json = serde_json::to_string_pretty(&serializable_provinces)?;
-    fs::write(cli.output.join("provinces.json"), provinces_json)?;
-    println!(
-        "✅ provinces.json сохранён ({} провинций)",
-        serializable_provinces.len()
-    );
+            .cloned()
+            .map(|r| SerializableRegion {
+                id: r.id,
+                color: r.color,
+                province_ids: r.province_ids,
+            })
+            .collect();
+
+        let regions_json = serde_json::to_string_pretty(&serializable_regions)?;
+        fs::write(output.join("regions.json"), regions_json)?;
+        println!(
+            "✅ regions.json сохранён ({} регионов)",
+            serializable_regions.len()
+        );
+    }
 
-    println!("📦 Экспорт данных регионов в regions.json...");
-    let serializable_regions: Vec<SerializableRegion> = regions
-        .into_iter()
-        .map(|r| SerializableRegion {
-            id: r.id,
-            color: r.color,
-            province_ids: r.province_ids,
-        })
-        .collect();
-
-    let regions_json = serde_json::to_string_pretty(&serializable_regions)?;
-    fs::write(cli.output.join("regions.json"), regions_json)?;
-    println!(
-        "✅ regions.json сохранён ({} регионов)",
-        serializable_regions.len()
-    );
+    // === ЭТАП 6: Единый файл мира ===
+    if export.bundle {
+        world = match world.into_world() {
+            Ok(full) => {
+                println!("📦 Сохранение единого файла мира в world.json...");
+                full.save(output.join("world.json").to_str().unwrap())?;
+                println!("✅ world.json сохранён");
+                full.into()
+            }
+            Err(partial) => partial,
+        };
+    }
 
     // === ЗАВЕРШЕНИЕ ===
     println!(
         "\n✅ Генерация завершена успешно! Результаты сохранены в: {}",
-        cli.output.display()
+        output.display()
     );
     println!("\n📊 Статистика мира:");
-    println!("   • Провинций: {}", serializable_provinces.len());
-    println!("   • Регионов: {}", serializable_regions.len());
-    println!("   • Стратегических точек: {}", strategic_points.len());
-    println!("   • Площадь суши: {:.1}%", land_ratio * 100.0);
+    if let Some(provinces) = &world.provinces {
+        println!("   • Провинций: {}", provinces.len());
+        let report = AreaReport::from_provinces(provinces);
+        for (label, stats) in [
+            ("континентальных", &report.continental),
+            ("островных", &report.island),
+            ("океанических", &report.oceanic),
+        ] {
+            if stats.count > 0 {
+                println!(
+                    "     – {label}: {}, площадь {}–{} (медиана {}, CV {:.2})",
+                    stats.count,
+                    stats.min,
+                    stats.max,
+                    stats.median,
+                    stats.coefficient_of_variation()
+                );
+            }
+        }
+    }
+    if let Some(regions) = &world.regions {
+        println!("   • Регионов: {}", regions.len());
+    }
+    if let Some(strategic_points) = &world.strategic_points {
+        println!("   • Стратегических точек: {}", strategic_points.len());
+    }
+    if let Some(land_ratio) = world.land_ratio() {
+        println!(
+            "   • Площадь суши: {:.1}% (цель {:.1}%, уровень моря {:.2})",
+            land_ratio * 100.0,
+            params.land_ratio() * 100.0,
+            params.sea_level
+        );
+    }
 
-    Ok(())
+    Ok(world)
 }
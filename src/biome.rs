@@ -16,6 +16,7 @@
 //! - Стоимость перемещения для геймплея
 //! - Чёткие переходные зоны с "размытием" для естественности
 
+use crate::error::{MapgenError, image_buffer};
 use fastnoise_lite::FastNoiseLite;
 use image::ImageBuffer;
 use serde::{Deserialize, Serialize};
@@ -26,8 +27,9 @@ use rayon::prelude::*;
 // === КОНСТАНТЫ ГЕНЕРАЦИИ ===
 // Скорректированы для работы с диапазоном высот [0.0, 1.0] и sea_level около 0.5
 const DEEP_OCEAN_DEPTH: f32 = 0.1;
-const MOUNTAIN_START: f32 = 0.75; // Высота начала гор
-const MOUNTAIN_PEAK: f32 = 0.85; // Высота ледников/скал
+// Пороги гор — доли высоты суши (от sea_level до 1.0): при sea_level = 0.5 это 0.75 и 0.85
+const MOUNTAIN_START_FRACTION: f32 = 0.5; // Начало гор
+const MOUNTAIN_PEAK_FRACTION: f32 = 0.7; // Ледники/скалы
 const ICE_TEMP_LIMIT: f32 = 0.1; // Температура замерзания воды
 // Сила "размытия" границы (влияет на то, насколько широкой будет переходная зона)
 const BOUNDARY_FUZZINESS: f32 = 0.15;
@@ -154,7 +156,7 @@ impl Biome {
 }
 
 /// Карта биомов — распределение ландшафтов по поверхности мира
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BiomeMap {
     /// Ширина карты в пикселях
     pub width: u32,
@@ -173,8 +175,11 @@ pub struct BiomeMap {
 ///    - Температура определяет состояние воды (жидкая/лёд)
 ///
 /// 2. **Горные биомы** (приоритет 1 — самый высокий для суши):
-///    - Высота > `MOUNTAIN_PEAK` → `GlacialMountain` (если холодно) или `RockyMountain`
-///    - Высота > `MOUNTAIN_START` → горы с учётом температуры
+///    - Пороги гор отсчитываются от уровня моря: `sea_level + доля × (1 − sea_level)`,
+///      поэтому горный пояс не опускается к побережью при высоком `sea_level`
+///    - Выше порога `MOUNTAIN_PEAK_FRACTION` → `GlacialMountain` (если холодно)
+///      или `RockyMountain`
+///    - Выше порога `MOUNTAIN_START_FRACTION` → горы с учётом температуры
 ///    - Горы имеют приоритет над климатом — реалистично для высокогорья
 ///
 /// 3. **Климатические биомы** (приоритет 2):
@@ -284,14 +289,15 @@ fn assign_biome_at_point(
 
         // ПРИОРИТЕТ 1: Горы всегда определяются по высоте первыми!
         // Сначала определяем, насколько холодно, потом какой тип горы
-        if elevation > MOUNTAIN_PEAK {
+        let land_height = 1.0 - sea_level;
+        if elevation > sea_level + MOUNTAIN_PEAK_FRACTION * land_height {
             // Если наверху холодно, это всегда GlacialMountain
             if temp < 0.3 {
                 return Biome::GlacialMountain;
             }
 
             return Biome::RockyMountain;
-        } else if elevation > MOUNTAIN_START {
+        } else if elevation > sea_level + MOUNTAIN_START_FRACTION * land_height {
             // Если на средней высоте холодно, это GlacialMountain, иначе RockyMountain
             if temp < 0.25 {
                 return Biome::GlacialMountain;
@@ -371,17 +377,17 @@ impl BiomeMap {
     /// * `path` — путь к файлу для сохранения
     ///
     /// # Ошибки
-    /// Возвращает ошибку, если не удаётся создать или записать файл.
+    /// - [`MapgenError::InvalidDimensions`] — размер данных не совпадает с размерами карты
+    /// - [`MapgenError::ImageEncoding`] — не удалось закодировать изображение
+    /// - [`MapgenError::Io`] — не удалось записать файл
     ///
     /// # Пример
     /// ```rust
     /// biome_map.save_as_png("output/biomes.png")?;
     /// ```
-    pub fn save_as_png(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save_as_png(&self, path: &str) -> Result<(), MapgenError> {
         let img: ImageBuffer<image::Rgba<u8>, Vec<u8>> =
-            ImageBuffer::from_raw(self.width, self.height, self.to_rgba_image())
-                .ok_or("Failed to create image buffer")?;
-        img.save(path)?;
-        Ok(())
+            image_buffer(self.width, self.height, self.to_rgba_image())?;
+        img.save(path).map_err(|e| MapgenError::image(path, e))
     }
 }
@@ -0,0 +1,300 @@
+// src/bundle.rs
+//! Единый файл мира (bundle)
+//!
+//! Этот модуль сохраняет сгенерированный [`World`] в один самоописываемый файл
+//! и загружает его обратно. Игровому клиенту достаточно одного артефакта вместо
+//! восстановления данных из цветных PNG.
+//!
+//! ## Формат файла
+//!
+//! Файл — компактный JSON со служебным заголовком и всеми слоями мира:
+//!
+//! ```json
+//! {
+//!   "format": "mapgen-world",
+//!   "format_version": 1,
+//!   "generator_version": "0.1.0",
+//!   "seed": 42,
+//!   "width": 2048,
+//!   "height": 1024,
+//!   "world": {
+//!     "params": { ... },
+//!     "heightmap": { "width": 2048, "height": 1024, "data": [ ... ] },
+//!     "temperature": [ ... ],
+//!     "humidity": [ ... ],
+//!     "biome_map": { ... },
+//!     "water_type": [ ... ],
+//!     "river_map": { ... },
+//!     "provinces": [ ... ],
+//!     "pixel_to_id": [ ... ],
+//!     "graph": {
+//!       "nodes": [0, 1, ...],
+//!       "edges": [
+//!         {
+//!           "provinces": [0, 1],
+//!           "length": 42,
+//!           "kind": "land_sea",
+//!           "crosses_river": false,
+//!           "crosses_mountains": false,
+//!           "movement_cost": 1.0
+//!         },
+//!         ...
+//!       ]
+//!     },
+//!     "regions": [ ... ],
+//!     "strategic_points": [ ... ]
+//!   }
+//! }
+//! ```
+//!
+//! - `format_version` увеличивается при несовместимых изменениях формата;
+//!   файлы другой версии отклоняются при загрузке
+//! - `generator_version` — версия библиотеки, создавшей файл (справочно)
+//! - Граф смежности хранится как список `province_id` узлов и рёбер с парой
+//!   `province_id` и атрибутами границы ([`ProvinceBorder`])
+//! - Вещественные значения записываются без потери точности
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::{World, WorldGenerationParams, generate_world};
+//!
+//! let params = WorldGenerationParams {
+//!     width: 96,
+//!     height: 48,
+//!     ..Default::default()
+//! };
+//! let world = generate_world(&params);
+//!
+//! let path = std::env::temp_dir().join("mapgen_doc_world.json");
+//! world.save(path.to_str().unwrap())?;
+//!
+//! let loaded = World::load(path.to_str().unwrap())?;
+//! assert_eq!(loaded.pixel_to_id, world.pixel_to_id);
+//! assert_eq!(loaded.graph.edge_count(), world.graph.edge_count());
+//! # Ok::<(), mapgen::MapgenError>(())
+//! ```
+
+use crate::error::MapgenError;
+use crate::province::graph::{ProvinceBorder, ProvinceGraph};
+use crate::world::World;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+/// Идентификатор формата файла мира
+pub const BUNDLE_FORMAT: &str = "mapgen-world";
+
+/// Текущая версия формата файла мира
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Версия генератора, записываемая в файл мира
+pub const GENERATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Заголовок файла мира (для проверки версии до разбора слоёв)
+#[derive(Deserialize)]
+struct BundleHeader {
+    format: String,
+    format_version: u32,
+    #[allow(dead_code)] // слои пропускаются без разбора
+    world: IgnoredAny,
+}
+
+/// Файл мира для записи (заимствует мир)
+#[derive(Serialize)]
+struct BundleRef<'a> {
+    format: &'a str,
+    format_version: u32,
+    generator_version: &'a str,
+    seed: u64,
+    width: u32,
+    height: u32,
+    world: &'a World,
+}
+
+/// Файл мира для чтения
+#[derive(Deserialize)]
+struct Bundle {
+    world: World,
+}
+
+/// Граф смежности в сериализуемом виде
+#[derive(Serialize, Deserialize)]
+struct GraphData {
+    /// `province_id` узлов в порядке индексов графа
+    nodes: Vec<u32>,
+    /// Рёбра графа
+    edges: Vec<StoredEdge>,
+}
+
+/// Ребро графа в файле мира
+#[derive(Serialize, Deserialize)]
+struct StoredEdge {
+    /// Пара `province_id`
+    provinces: [u32; 2],
+    /// Атрибуты границы
+    #[serde(flatten)]
+    border: ProvinceBorder,
+}
+
+impl World {
+    /// Сохраняет мир в единый файл (см. формат в документации модуля)
+    ///
+    /// # Параметры
+    /// * `path` — путь к файлу (например, `output/world.json`)
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — не удалось записать файл
+    pub fn save(&self, path: &str) -> Result<(), MapgenError> {
+        let io_error = |source| MapgenError::Io {
+            path: PathBuf::from(path),
+            source,
+        };
+        let file = fs::File::create(path).map_err(io_error)?;
+        let mut writer = BufWriter::new(file);
+        self.write_bundle(&mut writer).map_err(io_error)?;
+        writer.flush().map_err(io_error)
+    }
+
+    /// Записывает мир в формате файла мира в произвольный приёмник
+    ///
+    /// # Ошибки
+    /// Возвращает ошибку ввода-вывода приёмника.
+    pub fn write_bundle<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        let bundle = BundleRef {
+            format: BUNDLE_FORMAT,
+            format_version: BUNDLE_FORMAT_VERSION,
+            generator_version: GENERATOR_VERSION,
+            seed: self.params.seed,
+            width: self.width(),
+            height: self.height(),
+            world: self,
+        };
+        serde_json::to_writer(writer, &bundle).map_err(std::io::Error::from)
+    }
+
+    /// Загружает мир из файла, сохранённого [`World::save`]
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — файл не найден или не может быть прочитан
+    /// - [`MapgenError::InvalidData`] — файл повреждён, не является файлом мира
+    ///   или записан другой версией формата
+    /// - [`MapgenError::InvalidDimensions`] — размер слоя не совпадает с размерами мира
+    pub fn load(path: &str) -> Result<Self, MapgenError> {
+        let bytes = fs::read(path).map_err(|source| MapgenError::Io {
+            path: PathBuf::from(path),
+            source,
+        })?;
+        Self::from_bundle_slice(&bytes).map_err(|err| match err {
+            MapgenError::InvalidData { message, .. } => MapgenError::InvalidData {
+                path: Some(PathBuf::from(path)),
+                message,
+            },
+            err => err,
+        })
+    }
+
+    /// Разбирает мир из содержимого файла мира в памяти
+    ///
+    /// Удобно для клиентов, получающих файл по сети (например, WASM).
+    ///
+    /// # Ошибки
+    /// См. [`World::load`]; путь в [`MapgenError::InvalidData`] равен `None`.
+    pub fn from_bundle_slice(bytes: &[u8]) -> Result<Self, MapgenError> {
+        let invalid = |message: String| MapgenError::InvalidData {
+            path: None,
+            message,
+        };
+
+        let header: BundleHeader =
+            serde_json::from_slice(bytes).map_err(|e| invalid(e.to_string()))?;
+        if header.format != BUNDLE_FORMAT {
+            return Err(invalid(format!(
+                "неизвестный формат «{}», ожидается «{BUNDLE_FORMAT}»",
+                header.format
+            )));
+        }
+        if header.format_version != BUNDLE_FORMAT_VERSION {
+            return Err(invalid(format!(
+                "версия формата {} не поддерживается (ожидается {BUNDLE_FORMAT_VERSION})",
+                header.format_version
+            )));
+        }
+
+        let Bundle { world } = serde_json::from_slice(bytes).map_err(|e| invalid(e.to_string()))?;
+        world.check_dimensions()?;
+        Ok(world)
+    }
+
+    /// Проверяет, что размеры всех попиксельных слоёв совпадают с размерами мира
+    fn check_dimensions(&self) -> Result<(), MapgenError> {
+        let (width, height) = (self.width(), self.height());
+        let expected = width as usize * height as usize;
+        let lengths = [
+            self.heightmap.data.len(),
+            self.temperature.len(),
+            self.humidity.len(),
+            self.biome_map.data.len(),
+            self.water_type.len(),
+            self.river_map.data.len(),
+            self.pixel_to_id.len(),
+        ];
+        match lengths.into_iter().find(|&len| len != expected) {
+            Some(actual) => Err(MapgenError::InvalidDimensions {
+                width,
+                height,
+                expected,
+                actual,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Сериализует граф смежности как списки узлов и рёбер (`province_id` и атрибуты)
+pub(crate) fn serialize_graph<S: Serializer>(
+    graph: &ProvinceGraph,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    GraphData {
+        nodes: graph.node_weights().copied().collect(),
+        edges: graph
+            .edge_references()
+            .map(|e| StoredEdge {
+                provinces: [graph[e.source()], graph[e.target()]],
+                border: *e.weight(),
+            })
+            .collect(),
+    }
+    .serialize(serializer)
+}
+
+/// Восстанавливает граф смежности из списков узлов и рёбер
+pub(crate) fn deserialize_graph<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ProvinceGraph, D::Error> {
+    let data = GraphData::deserialize(deserializer)?;
+    let mut graph = ProvinceGraph::with_capacity(data.nodes.len(), data.edges.len());
+    let id_to_node: HashMap<u32, NodeIndex> = data
+        .nodes
+        .iter()
+        .map(|&id| (id, graph.add_node(id)))
+        .collect();
+    for edge in data.edges {
+        let StoredEdge {
+            provinces: [a, b],
+            border,
+        } = edge;
+        let (Some(&na), Some(&nb)) = (id_to_node.get(&a), id_to_node.get(&b)) else {
+            return Err(serde::de::Error::custom(format!(
+                "ребро {a}–{b} ссылается на отсутствующую провинцию"
+            )));
+        };
+        graph.add_edge(na, nb, border);
+    }
+    Ok(graph)
+}
@@ -0,0 +1,255 @@
+// src/config/layers.rs
+//! Многослойная загрузка конфигурации
+//!
+//! Итоговые параметры собираются из трёх слоёв, каждый из которых
+//! переопределяет предыдущий **по отдельным полям**:
+//!
+//! 1. **Пресет мира** — климат и рельеф по умолчанию для выбранного типа мира
+//!    (`WorldType::preset` или пользовательский пресет)
+//! 2. **Файл конфигурации** — значения, явно указанные в TOML
+//! 3. **Переопределения** — значения из командной строки (`--set ключ=значение`,
+//!    см. [`add_override`]) или кода
+//!
+//! Поэтому `IceAgeEarth` с секцией `[climate]`, в которой указан только
+//! `global_humidity_offset`, сохраняет ледниковые температуру и полярное усиление,
+//! а поля, не указанные ни в одном слое, получают значения по умолчанию из serde.
+//!
+//! Неизвестные ключи (например, опечатка `--set sea_levle=0.3`) не отбрасываются
+//! молча: разбор завершается ошибкой [`MapgenError::ConfigParse`] с именем ключа.
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::WorldGenerationParams;
+//!
+//! let mut overrides = toml::Table::new();
+//! overrides.insert("seed".into(), toml::Value::Integer(7));
+//!
+//! let params = WorldGenerationParams::from_toml_str_with_overrides(
+//!     "seed = 1\nworld_type = \"IceAgeEarth\"\n[climate]\nglobal_humidity_offset = 0.3",
+//!     &overrides,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(params.seed, 7); // переопределение
+//! assert_eq!(params.climate.global_humidity_offset, 0.3); // файл
+//! assert_eq!(params.climate.polar_amplification, 1.8); // пресет IceAgeEarth
+//! ```
+
+use super::{ClimateSettings, TerrainSettings, WorldGenerationParams, WorldPreset, WorldType};
+use crate::error::MapgenError;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Слой значений по умолчанию, который задаёт пресет мира
+#[derive(Serialize)]
+struct PresetDefaults<'a> {
+    climate: &'a ClimateSettings,
+    terrain: &'a TerrainSettings,
+}
+
+/// Рекурсивно накладывает таблицу `overlay` на таблицу `base`
+///
+/// Вложенные таблицы сливаются по полям, остальные значения (включая массивы)
+/// из `overlay` заменяют значения в `base`.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::config::layers::merge_tables;
+///
+/// let mut base: toml::Table = toml::from_str("[climate]\na = 1\nb = 2").unwrap();
+/// let overlay: toml::Table = toml::from_str("[climate]\nb = 3").unwrap();
+/// merge_tables(&mut base, overlay);
+///
+/// assert_eq!(base["climate"]["a"].as_integer(), Some(1));
+/// assert_eq!(base["climate"]["b"].as_integer(), Some(3));
+/// ```
+pub fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+impl WorldGenerationParams {
+    /// Создаёт параметры по умолчанию с климатом и рельефом пресета типа мира
+    ///
+    /// В отличие от `Default::default()` с заменённым `world_type`, климат и рельеф
+    /// соответствуют выбранному типу мира.
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::{WorldGenerationParams, WorldType};
+    ///
+    /// let params = WorldGenerationParams::for_world_type(WorldType::IceAgeEarth);
+    /// assert_eq!(params.climate.global_temperature_offset, -0.7);
+    /// ```
+    #[must_use]
+    pub fn for_world_type(world_type: WorldType) -> Self {
+        let preset = world_type.preset();
+        Self {
+            world_type,
+            climate: preset.climate,
+            terrain: preset.terrain,
+            ..Self::default()
+        }
+    }
+
+    /// Загружает параметры из TOML-файла с переопределениями поверх файла
+    ///
+    /// # Параметры
+    /// * `path` — путь к файлу конфигурации
+    /// * `overrides` — таблица значений, переопределяющих файл (структура как в TOML)
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — файл конфигурации или пресетов не может быть прочитан
+    /// - [`MapgenError::ConfigParse`] — недопустимый формат файла или переопределения
+    ///   либо неизвестный ключ
+    /// - [`MapgenError::UnknownPreset`] — указанный пресет не найден
+    pub fn from_toml_file_with_overrides(
+        path: &str,
+        overrides: &toml::Table,
+    ) -> Result<Self, MapgenError> {
+        let contents = fs::read_to_string(path).map_err(|source| MapgenError::Io {
+            path: PathBuf::from(path),
+            source,
+        })?;
+        let path = Path::new(path);
+        Self::from_layers(&contents, Some(path), path.parent(), overrides)
+    }
+
+    /// Разбирает параметры из строки TOML с переопределениями поверх неё
+    ///
+    /// Относительный `presets_file` разрешается от текущего каталога.
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::ConfigParse`] — недопустимый формат текста или переопределения
+    ///   либо неизвестный ключ
+    /// - [`MapgenError::UnknownPreset`] — указанный пресет не найден
+    pub fn from_toml_str_with_overrides(
+        contents: &str,
+        overrides: &toml::Table,
+    ) -> Result<Self, MapgenError> {
+        Self::from_layers(contents, None, None, overrides)
+    }
+
+    /// Собирает параметры из слоёв: пресет → файл → переопределения
+    ///
+    /// # Алгоритм
+    /// 1. Разбор файла в таблицу и наложение на неё переопределений
+    /// 2. Определение пресета по объединённым значениям (тип мира может быть переопределён)
+    /// 3. Наложение объединённой таблицы на климат и рельеф пресета
+    ///
+    /// Ошибки типов и неизвестные ключи, допущенные в самом файле, получают номер
+    /// строки и столбца.
+    fn from_layers(
+        contents: &str,
+        path: Option<&Path>,
+        base_dir: Option<&Path>,
+        overrides: &toml::Table,
+    ) -> Result<Self, MapgenError> {
+        let mut user: toml::Table =
+            toml::from_str(contents).map_err(|e| MapgenError::config_parse(path, contents, &e))?;
+        merge_tables(&mut user, overrides.clone());
+
+        let mut probe = Self::from_table(user.clone(), path).map_err(|err| {
+            // Ошибка в самом файле: повторный разбор текста даёт строку и столбец
+            match toml::from_str::<Self>(contents) {
+                Err(e) => MapgenError::config_parse(path, contents, &e),
+                Ok(_) => err,
+            }
+        })?;
+        probe.resolve_preset(base_dir)?;
+        let preset: WorldPreset = probe.preset();
+
+        let mut merged = toml::Table::try_from(PresetDefaults {
+            climate: &preset.climate,
+            terrain: &preset.terrain,
+        })
+        .unwrap_or_default();
+        merge_tables(&mut merged, user);
+
+        let mut params = Self::from_table(merged, path)?;
+        params.world_type = probe.world_type;
+        params.custom_preset = probe.custom_preset;
+        Ok(params)
+    }
+
+    /// Десериализует параметры из объединённой таблицы
+    fn from_table(table: toml::Table, path: Option<&Path>) -> Result<Self, MapgenError> {
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| MapgenError::config_parse(path, "", &e))
+    }
+}
+
+/// Добавляет переопределение вида `ключ=значение` в таблицу переопределений
+///
+/// Ключ может быть составным (`terrain.total_provinces`) — недостающие вложенные
+/// таблицы создаются автоматически. Значение разбирается как значение TOML
+/// (`7`, `0.45`, `true`, `"EarthLike"`); если это не удаётся, оно сохраняется
+/// как строка, поэтому кавычки для строк необязательны (`world_type=Archipelago`).
+///
+/// # Ошибки
+/// Возвращает [`MapgenError::ConfigParse`], если в строке нет `=` или ключ пуст.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::config::layers::add_override;
+///
+/// let mut overrides = toml::Table::new();
+/// add_override(&mut overrides, "seed=7").unwrap();
+/// add_override(&mut overrides, "terrain.total_provinces=200").unwrap();
+/// add_override(&mut overrides, "world_type=Archipelago").unwrap();
+///
+/// assert_eq!(overrides["seed"].as_integer(), Some(7));
+/// assert_eq!(overrides["terrain"]["total_provinces"].as_integer(), Some(200));
+/// assert_eq!(overrides["world_type"].as_str(), Some("Archipelago"));
+/// assert!(add_override(&mut overrides, "seed").is_err());
+/// ```
+pub fn add_override(overrides: &mut toml::Table, assignment: &str) -> Result<(), MapgenError> {
+    let invalid = |message: &str| MapgenError::ConfigParse {
+        path: None,
+        message: format!("переопределение «{assignment}»: {message}"),
+        line: None,
+        column: None,
+    };
+
+    let (key, raw) = assignment
+        .split_once('=')
+        .ok_or_else(|| invalid("ожидается формат ключ=значение"))?;
+    let segments: Vec<&str> = key.trim().split('.').map(str::trim).collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return Err(invalid("пустой ключ"));
+    }
+
+    let raw = raw.trim();
+    let value = toml::from_str::<toml::Table>(&format!("value = {raw}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()));
+
+    let (last, parents) = segments
+        .split_last()
+        .ok_or_else(|| invalid("пустой ключ"))?;
+    let mut table = overrides;
+    for segment in parents {
+        let entry = table
+            .entry((*segment).to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if !entry.is_table() {
+            *entry = toml::Value::Table(toml::Table::new());
+        }
+        table = entry
+            .as_table_mut()
+            .ok_or_else(|| invalid("ожидается таблица"))?;
+    }
+    table.insert((*last).to_string(), value);
+    Ok(())
+}
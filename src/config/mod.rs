@@ -0,0 +1,566 @@
+// src/config/mod.rs
+//! Конфигурация генерации мира
+//!
+//! Этот модуль определяет все параметры, управляющие процедурной генерацией мира:
+//! - Типы миров (землеподобный, архипелаг и т.д.) и пользовательские пресеты (`preset`)
+//! - Климатические настройки
+//! - Параметры рельефа и островов
+//! - Настройки провинций и регионов (включая политику слияния мелких провинций)
+//! - Многослойную загрузку: пресет → файл → переопределения (`layers`)
+//! - Проверку параметров перед генерацией (`validation`)
+//!
+//! Все структуры поддерживают сериализацию в TOML/JSON для удобной настройки через конфигурационные файлы.
+
+pub mod layers;
+pub mod preset;
+pub mod validation;
+
+pub use preset::WorldPreset;
+pub use validation::{Severity, ValidationIssue};
+
+use crate::error::MapgenError;
+use crate::province::area::AreaSettings;
+use crate::province::growth::GrowthSettings;
+use crate::province::merge::MergePolicy;
+use crate::province::seeding::SeedingSettings;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Тип генерируемого мира
+///
+/// Определяет глобальную структуру карты: распределение суши/моря, форму континентов и климатические особенности.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum WorldType {
+    /// Землеподобный мир с несколькими континентами и океанами (≈30% суши)
+    #[default]
+    EarthLike,
+    /// Один крупный суперконтинент с небольшими островами (≈70% суши)
+    Supercontinent,
+    /// Многочисленные острова и архипелаги, мало крупных континентов (≈15% суши)
+    Archipelago,
+    /// Большое внутреннее море, окружённое континентами (≈25% суши)
+    Mediterranean,
+    /// Ледниковый период: расширенные полярные шапки, больше льда и тундры (≈35% "суши", но большая часть непригодна)
+    IceAgeEarth,
+    /// Средиземноморье с преобладанием пустынь и засушливых биомов (≈20% суши)
+    DesertMediterranean,
+    /// Пользовательский архетип, заданный пресетом в конфигурации
+    /// (секция `[custom_preset]` или `preset` из `presets_file`)
+    Custom,
+}
+
+impl WorldType {
+    /// Возвращает встроенный пресет для данного типа мира.
+    ///
+    /// Для `Custom` возвращается пресет по умолчанию (`EarthLike`) — фактический
+    /// пользовательский пресет хранится в [`WorldGenerationParams::custom_preset`].
+    ///
+    /// # Особенности
+    /// - `Supercontinent` и `Mediterranean` — низкочастотный шум с 3 октавами (крупные массивы суши)
+    /// - `Archipelago` — квадратичная кривая контраста для множества островов
+    /// - `IceAgeEarth` — пониженная температура и расширенные полярные зоны
+    ///
+    /// # Пример
+    /// ```
+    /// use mapgen::config::WorldType;
+    /// let preset = WorldType::Archipelago.preset();
+    /// assert_eq!(preset.octaves, 4);
+    /// assert_eq!(preset.contrast, 2.0);
+    /// ```
+    #[must_use]
+    pub fn preset(self) -> WorldPreset {
+        let base = WorldPreset::default();
+        let smooth_terrain = TerrainSettings {
+            elevation_power: 0.65,
+            smooth_radius: 2,
+            mountain_compression: 0.8,
+            total_provinces: 80,
+        };
+        match self {
+            WorldType::EarthLike | WorldType::Custom => base,
+            WorldType::Supercontinent => WorldPreset {
+                land_ratio: 0.70,
+                octaves: 3,
+                base_frequency: 0.002,
+                terrain: smooth_terrain,
+                ..base
+            },
+            WorldType::Archipelago => WorldPreset {
+                land_ratio: 0.15,
+                octaves: 4,
+                contrast: 2.0,
+                terrain: TerrainSettings {
+                    elevation_power: 0.75,
+                    smooth_radius: 1,
+                    mountain_compression: 0.5,
+                    total_provinces: 120,
+                },
+                ..base
+            },
+            WorldType::Mediterranean => WorldPreset {
+                land_ratio: 0.25,
+                octaves: 3,
+                base_frequency: 0.002,
+                terrain: smooth_terrain,
+                ..base
+            },
+            // больше льда = больше "суши", но непригодной
+            WorldType::IceAgeEarth => WorldPreset {
+                land_ratio: 0.35,
+                climate: ClimateSettings {
+                    global_temperature_offset: -0.7,
+                    global_humidity_offset: 0.0,
+                    polar_amplification: 1.8,
+                    climate_latitude_exponent: 1.2, // расширенные полюсы
+                },
+                ..base
+            },
+            WorldType::DesertMediterranean => WorldPreset {
+                land_ratio: 0.20,
+                ..base
+            },
+        }
+    }
+
+    /// Возвращает целевую долю суши для данного типа мира.
+    ///
+    /// # Возвращает
+    /// - `0.0` — полностью водный мир
+    /// - `1.0` — полностью сухопутный мир
+    ///
+    /// # Примеры
+    /// ```
+    /// use mapgen::config::WorldType;
+    /// assert_eq!(WorldType::EarthLike.target_land_ratio(), 0.30);
+    /// assert_eq!(WorldType::Archipelago.target_land_ratio(), 0.15);
+    /// ```
+    #[must_use]
+    pub fn target_land_ratio(self) -> f32 {
+        self.preset().land_ratio
+    }
+
+    /// Возвращает настройки климата по умолчанию для данного типа мира.
+    ///
+    /// # Особенности
+    /// - `IceAgeEarth` имеет пониженную глобальную температуру и расширенные полярные зоны
+    /// - Остальные типы используют умеренный климат с сжатыми полюсами для увеличения играбельной зоны
+    #[must_use]
+    pub fn default_climate(&self) -> ClimateSettings {
+        self.preset().climate
+    }
+
+    /// Возвращает настройки рельефа по умолчанию для данного типа мира.
+    ///
+    /// # Особенности
+    /// - `Supercontinent` и `Mediterranean` имеют более сглаженный рельеф (меньше гор)
+    /// - `Archipelago` имеет более резкий рельеф для создания драматичных островов
+    #[must_use]
+    pub fn default_terrain(&self) -> TerrainSettings {
+        self.preset().terrain
+    }
+}
+
+/// Глобальные климатические модификаторы
+///
+/// Управляет распределением температуры и влажности по широте и высоте.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClimateSettings {
+    /// Глобальный сдвиг температуры (-1.0 = очень холодно, +1.0 = очень жарко)
+    #[serde(default = "default_temperature_offset")]
+    pub global_temperature_offset: f32,
+
+    /// Глобальный сдвиг влажности (-1.0 = очень сухо, +1.0 = очень влажно)
+    #[serde(default = "default_humidity_offset")]
+    pub global_humidity_offset: f32,
+
+    /// Усиление полярного эффекта:
+    /// - `1.0` — стандартное охлаждение к полюсам
+    /// - `>1.0` — более резкое охлаждение (широкие тундры/льды)
+    /// - `<1.0` — более мягкое охлаждение (узкие полярные зоны)
+    #[serde(default = "default_polar_amplification")]
+    pub polar_amplification: f32,
+
+    /// Экспонента для сжатия полярных зон по широте:
+    /// - `<1.0` → сжимает полюсы (больше умеренных зон),
+    /// - `=1.0` → линейно,
+    /// - `>1.0` → расширяет полюсы.
+    #[serde(default = "default_climate_latitude_exponent")]
+    pub climate_latitude_exponent: f32,
+}
+
+fn default_temperature_offset() -> f32 {
+    0.0
+}
+fn default_humidity_offset() -> f32 {
+    0.0
+}
+fn default_polar_amplification() -> f32 {
+    1.0
+}
+fn default_climate_latitude_exponent() -> f32 {
+    0.65
+}
+
+impl Default for ClimateSettings {
+    fn default() -> Self {
+        Self {
+            global_temperature_offset: 0.0,
+            global_humidity_offset: 0.0,
+            polar_amplification: 1.0,
+            climate_latitude_exponent: 0.65,
+        }
+    }
+}
+
+/// Настройки островов в океане
+///
+/// Управляет генерацией мелких островов в открытых океанах.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IslandSettings {
+    /// Плотность островов (0.0 = нет островов, 1.0 = очень много островов)
+    #[serde(default = "default_island_density")]
+    pub island_density: f32,
+
+    /// Минимальный размер острова в пикселях (острова меньше этого размера уходят под воду)
+    #[serde(default = "default_min_island_size")]
+    pub min_island_size: u32,
+}
+
+fn default_island_density() -> f32 {
+    0.2
+}
+fn default_min_island_size() -> u32 {
+    200
+}
+
+impl Default for IslandSettings {
+    fn default() -> Self {
+        Self {
+            island_density: 0.2,
+            min_island_size: 200,
+        }
+    }
+}
+
+/// Настройки рельефа и провинций
+///
+/// Управляет формой ландшафта и количеством административных единиц.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TerrainSettings {
+    /// Степень нелинейности высоты:
+    /// - `<1.0` → сглаживает рельеф (меньше гор, больше равнин),
+    /// - `=1.0` → линейно,
+    /// - `>1.0` → усиливает рельеф (более резкие горы и долины).
+    #[serde(default = "default_elevation_power")]
+    pub elevation_power: f32,
+
+    /// Радиус сглаживания в пикселях (0 = без сглаживания)
+    #[serde(default = "default_smooth_radius")]
+    pub smooth_radius: usize,
+
+    /// Сжатие горных зон при назначении биомов:
+    /// - `0.0` = линейно,
+    /// - `1.0` = сильное сжатие (горы только на самых высоких пиках).
+    #[serde(default = "default_mountain_compression")]
+    pub mountain_compression: f32,
+
+    /// Общее количество провинций (суша + море)
+    #[serde(default = "default_total_provinces")]
+    pub total_provinces: usize,
+}
+
+fn default_elevation_power() -> f32 {
+    0.8
+}
+fn default_smooth_radius() -> usize {
+    1
+}
+fn default_mountain_compression() -> f32 {
+    0.7
+}
+fn default_total_provinces() -> usize {
+    120
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        Self {
+            elevation_power: 0.8,
+            smooth_radius: 1,
+            mountain_compression: 0.7,
+            total_provinces: 120,
+        }
+    }
+}
+
+/// Основные параметры генерации мира
+///
+/// Полная конфигурация для генерации одного мира. Поддерживает загрузку из TOML-файлов.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorldGenerationParams {
+    /// Сид генератора случайных чисел (детерминированная генерация)
+    pub seed: u64,
+
+    /// Ширина карты в пикселях (по умолчанию 2048)
+    #[serde(default = "default_width")]
+    pub width: u32,
+
+    /// Высота карты в пикселях (по умолчанию 1024)
+    #[serde(default = "default_height")]
+    pub height: u32,
+
+    /// Тип генерируемого мира (по умолчанию `EarthLike`)
+    #[serde(default)]
+    pub world_type: WorldType,
+
+    /// Пользовательский пресет мира (используется при `world_type = "Custom"`)
+    ///
+    /// Задаётся секцией `[custom_preset]` или загружается из `presets_file` по имени `preset`.
+    #[serde(default)]
+    pub custom_preset: Option<WorldPreset>,
+
+    /// Путь к библиотеке пресетов (относительно файла конфигурации)
+    #[serde(default)]
+    pub presets_file: Option<PathBuf>,
+
+    /// Имя пресета из `presets_file`; при указании тип мира становится `Custom`
+    #[serde(default)]
+    pub preset: Option<String>,
+
+    /// Уровень моря в диапазоне высот 0.0–1.0 (по умолчанию 0.5)
+    ///
+    /// Пиксели ниже этого уровня считаются водой на всех этапах генерации:
+    /// климат, биомы, классификация воды, реки и провинции.
+    #[serde(default = "default_sea_level")]
+    pub sea_level: f32,
+
+    /// Целевая доля суши (0.0–1.0), переопределяющая `WorldType::target_land_ratio`
+    ///
+    /// Позволяет получить затопленный или осушенный вариант мира с тем же сидом:
+    /// рельеф остаётся прежним, меняется только положение береговой линии.
+    #[serde(default)]
+    pub target_land_ratio: Option<f32>,
+
+    /// Климатические настройки (по умолчанию умеренный климат)
+    #[serde(default)]
+    pub climate: ClimateSettings,
+
+    /// Настройки островов в океане (по умолчанию умеренная плотность)
+    #[serde(default)]
+    pub islands: IslandSettings,
+
+    /// Целевое количество регионов (групп провинций)
+    ///
+    /// Определяет средний размер региона: `провинции / num_regions`. Фактическое
+    /// количество может быть больше, так как суша и море группируются раздельно.
+    #[serde(default = "default_num_regions")]
+    pub num_regions: usize,
+
+    /// Масштаб морских провинций относительно сухопутных (по умолчанию 2.5 = морские провинции крупнее)
+    ///
+    /// Провинции распределяются между сушей и морем пропорционально площади так,
+    /// чтобы средняя морская провинция была в `sea_province_scale` раз больше сухопутной.
+    #[serde(default = "default_sea_province_scale")]
+    pub sea_province_scale: f32,
+
+    /// Настройки рельефа и провинций
+    #[serde(default)]
+    pub terrain: TerrainSettings,
+
+    /// Размещение семян провинций (режим, итерации релаксации)
+    #[serde(default)]
+    pub seeding: SeedingSettings,
+
+    /// Рост провинций от семян (режим, веса стоимости расширения)
+    #[serde(default)]
+    pub growth: GrowthSettings,
+
+    /// Политика слияния мелких провинций (порог, стратегия, мелкие острова)
+    #[serde(default)]
+    pub merge: MergePolicy,
+
+    /// Целевая площадь провинций по типам и балансировка размеров
+    #[serde(default)]
+    pub area: AreaSettings,
+}
+
+impl WorldGenerationParams {
+    /// Загружает параметры из TOML-файла
+    ///
+    /// Климат и рельеф, не указанные в файле, берутся из пресета типа мира
+    /// (по отдельным полям, см. `config::layers`).
+    ///
+    /// # Аргументы
+    /// * `path` - путь к файлу конфигурации в формате TOML
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — файл не найден или не может быть прочитан
+    /// - [`MapgenError::ConfigParse`] — файл содержит недопустимый формат
+    ///   (с номером строки и столбца ошибки)
+    ///
+    /// # Пример
+    /// ```toml
+    /// # world.toml
+    /// seed = 42
+    /// width = 1024
+    /// height = 512
+    /// world_type = "Archipelago"
+    /// ```
+    ///
+    /// ```rust
+    /// let params = WorldGenerationParams::from_toml_file("world.toml")?;
+    /// ```
+    pub fn from_toml_file(path: &str) -> Result<Self, MapgenError> {
+        Self::from_toml_file_with_overrides(path, &toml::Table::new())
+    }
+
+    /// Разбирает параметры из строки в формате TOML
+    ///
+    /// # Ошибки
+    /// Возвращает [`MapgenError::ConfigParse`] с номером строки и столбца,
+    /// если текст содержит синтаксическую ошибку или поле недопустимого типа.
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::{MapgenError, WorldGenerationParams};
+    ///
+    /// let params = WorldGenerationParams::from_toml_str("seed = 42\nwidth = 1024").unwrap();
+    /// assert_eq!(params.width, 1024);
+    ///
+    /// let err = WorldGenerationParams::from_toml_str("seed = 42\nwidth = \"wide\"").unwrap_err();
+    /// assert!(matches!(err, MapgenError::ConfigParse { line: Some(2), .. }));
+    /// ```
+    pub fn from_toml_str(contents: &str) -> Result<Self, MapgenError> {
+        Self::from_toml_str_with_overrides(contents, &toml::Table::new())
+    }
+
+    /// Загружает именованный пресет из `presets_file` в `custom_preset`
+    ///
+    /// # Параметры
+    /// * `base_dir` — каталог, относительно которого разрешается `presets_file`
+    ///   (каталог файла конфигурации; `None` — текущий каталог)
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] / [`MapgenError::ConfigParse`] — ошибка чтения библиотеки пресетов
+    /// - [`MapgenError::UnknownPreset`] — пресет не найден или не указан `presets_file`
+    fn resolve_preset(&mut self, base_dir: Option<&Path>) -> Result<(), MapgenError> {
+        let Some(name) = &self.preset else {
+            return Ok(());
+        };
+        let Some(file) = &self.presets_file else {
+            return Err(MapgenError::UnknownPreset {
+                name: name.clone(),
+                available: Vec::new(),
+            });
+        };
+
+        let path = match base_dir {
+            Some(dir) if file.is_relative() => dir.join(file),
+            _ => file.clone(),
+        };
+        let mut presets = WorldPreset::load_presets(&path)?;
+        let preset = presets
+            .remove(name)
+            .ok_or_else(|| MapgenError::UnknownPreset {
+                name: name.clone(),
+                available: presets.keys().cloned().collect(),
+            })?;
+
+        self.world_type = WorldType::Custom;
+        self.custom_preset = Some(preset);
+        Ok(())
+    }
+
+    /// Возвращает фактический пресет мира
+    ///
+    /// Для `WorldType::Custom` — `custom_preset` (или пресет по умолчанию, если он не задан),
+    /// для остальных типов — встроенный пресет `WorldType::preset`.
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::WorldGenerationParams;
+    ///
+    /// let params = WorldGenerationParams::from_toml_str(
+    ///     "seed = 1\nworld_type = \"Custom\"\n[custom_preset]\nland_ratio = 0.45\ncontrast = 1.5",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(params.preset().contrast, 1.5);
+    /// assert_eq!(params.land_ratio(), 0.45);
+    /// ```
+    #[must_use]
+    pub fn preset(&self) -> WorldPreset {
+        match (&self.world_type, &self.custom_preset) {
+            (WorldType::Custom, Some(preset)) => preset.clone(),
+            _ => self.world_type.preset(),
+        }
+    }
+
+    /// Возвращает целевую долю суши с учётом переопределения
+    ///
+    /// # Возвращает
+    /// `target_land_ratio`, если он задан, иначе долю суши пресета мира.
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::{WorldGenerationParams, WorldType};
+    ///
+    /// let mut params = WorldGenerationParams {
+    ///     world_type: WorldType::Archipelago,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(params.land_ratio(), 0.15);
+    ///
+    /// params.target_land_ratio = Some(0.05); // затопленный архипелаг
+    /// assert_eq!(params.land_ratio(), 0.05);
+    /// ```
+    #[must_use]
+    pub fn land_ratio(&self) -> f32 {
+        self.target_land_ratio
+            .unwrap_or_else(|| self.preset().land_ratio)
+    }
+}
+
+fn default_width() -> u32 {
+    2048
+}
+fn default_height() -> u32 {
+    1024
+}
+fn default_sea_level() -> f32 {
+    0.5
+}
+fn default_num_regions() -> usize {
+    12
+}
+fn default_sea_province_scale() -> f32 {
+    2.5
+}
+
+impl Default for WorldGenerationParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            width: 2048,
+            height: 1024,
+            world_type: WorldType::EarthLike,
+            custom_preset: None,
+            presets_file: None,
+            preset: None,
+            sea_level: 0.5,
+            target_land_ratio: None,
+            climate: ClimateSettings::default(),
+            islands: IslandSettings::default(),
+            num_regions: 12,
+            sea_province_scale: 2.5,
+            terrain: TerrainSettings::default(),
+            seeding: SeedingSettings::default(),
+            growth: GrowthSettings::default(),
+            merge: MergePolicy::default(),
+            area: AreaSettings::default(),
+        }
+    }
+}
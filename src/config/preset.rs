@@ -0,0 +1,127 @@
+// src/config/preset.rs
+//! Пресеты типов мира
+//!
+//! Пресет описывает архетип мира целиком: долю суши, параметры шума карты высот,
+//! кривую контраста и настройки климата и рельефа по умолчанию. Встроенные типы
+//! (`EarthLike`, `Archipelago`, ...) — это готовые пресеты (`WorldType::preset`),
+//! а тип `Custom` позволяет задать собственный архетип в данных без изменения кода.
+//!
+//! ## Источники пресетов
+//!
+//! - **Встроенный**: `world_type = "EarthLike"` и т.д.
+//! - **Во встроенной таблице конфигурации**: `world_type = "Custom"` + секция `[custom_preset]`
+//! - **Из библиотеки пресетов**: `presets_file = "presets.toml"` + `preset = "twin_continents"`
+//!
+//! ## Формат библиотеки пресетов
+//! ```toml
+//! [twin_continents]
+//! land_ratio = 0.4
+//! octaves = 3
+//! base_frequency = 0.0025
+//! contrast = 1.3
+//!
+//! [twin_continents.terrain]
+//! total_provinces = 100
+//!
+//! [ring_world]
+//! land_ratio = 0.2
+//! contrast = 1.8
+//! ```
+//!
+//! Пропущенные поля берутся из пресета `EarthLike`.
+
+use super::{ClimateSettings, TerrainSettings};
+use crate::error::MapgenError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Пресет мира — набор параметров, определяющих архетип карты
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorldPreset {
+    /// Целевая доля суши (0.0–1.0)
+    pub land_ratio: f32,
+
+    /// Количество октав фрактального шума:
+    /// - `3` — крупные сглаженные массивы суши
+    /// - `5` — изрезанные береговые линии
+    pub octaves: i32,
+
+    /// Базовая частота шума:
+    /// - `0.002` — несколько крупных континентов
+    /// - `0.005` — много средних массивов суши
+    pub base_frequency: f32,
+
+    /// Кривая контраста — степень, в которую возводится базовый шум:
+    /// - `1.0` — линейно,
+    /// - `>1.0` — низины расширяются, суша распадается на острова (`2.0` у архипелага).
+    pub contrast: f32,
+
+    /// Климатические настройки по умолчанию
+    pub climate: ClimateSettings,
+
+    /// Настройки рельефа по умолчанию
+    pub terrain: TerrainSettings,
+}
+
+impl Default for WorldPreset {
+    fn default() -> Self {
+        Self {
+            land_ratio: 0.30,
+            octaves: 5,
+            base_frequency: 0.005,
+            contrast: 1.0,
+            climate: ClimateSettings::default(),
+            terrain: TerrainSettings::default(),
+        }
+    }
+}
+
+impl WorldPreset {
+    /// Загружает библиотеку именованных пресетов из TOML-файла
+    ///
+    /// Каждая таблица верхнего уровня файла — отдельный пресет.
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — файл не найден или не может быть прочитан
+    /// - [`MapgenError::ConfigParse`] — файл содержит недопустимый формат
+    ///
+    /// # Пример
+    /// ```rust,no_run
+    /// use mapgen::config::WorldPreset;
+    /// use std::path::Path;
+    ///
+    /// let presets = WorldPreset::load_presets(Path::new("examples/presets.toml"))?;
+    /// let ring = &presets["ring_world"];
+    /// # Ok::<(), mapgen::MapgenError>(())
+    /// ```
+    pub fn load_presets(path: &Path) -> Result<BTreeMap<String, WorldPreset>, MapgenError> {
+        let contents = fs::read_to_string(path).map_err(|source| MapgenError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse_presets(&contents, Some(path))
+    }
+
+    /// Разбирает библиотеку пресетов из строки в формате TOML
+    ///
+    /// # Ошибки
+    /// Возвращает [`MapgenError::ConfigParse`] с номером строки и столбца.
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::config::WorldPreset;
+    ///
+    /// let presets = WorldPreset::parse_presets("[ring_world]\nland_ratio = 0.2", None).unwrap();
+    /// assert_eq!(presets["ring_world"].land_ratio, 0.2);
+    /// assert_eq!(presets["ring_world"].octaves, 5); // значение по умолчанию
+    /// ```
+    pub fn parse_presets(
+        contents: &str,
+        path: Option<&Path>,
+    ) -> Result<BTreeMap<String, WorldPreset>, MapgenError> {
+        toml::from_str(contents).map_err(|e| MapgenError::config_parse(path, contents, &e))
+    }
+}
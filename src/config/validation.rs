@@ -0,0 +1,433 @@
+// src/config/validation.rs
+//! Проверка параметров генерации мира
+//!
+//! Этот модуль реализует проверку [`WorldGenerationParams`] до запуска генерации.
+//! Некорректные значения (нулевые размеры карты, радиус сглаживания больше карты,
+//! сдвиги климата вне допустимого диапазона и т.д.) иначе приводят к панике
+//! в глубине конвейера или к незаметно испорченному миру.
+//!
+//! ## Уровни серьёзности
+//!
+//! - **`Error`** — генерация с такими параметрами невозможна или даст заведомо
+//!   некорректный результат
+//! - **`Warning`** — генерация возможна, но результат, скорее всего, отличается
+//!   от ожидаемого (например, часть провинций будет слита из-за нехватки площади)
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::WorldGenerationParams;
+//! use mapgen::config::Severity;
+//!
+//! let params = WorldGenerationParams {
+//!     width: 0,
+//!     ..Default::default()
+//! };
+//!
+//! let issues = params.validate();
+//! assert!(issues.iter().any(|i| i.field == "width" && i.severity == Severity::Error));
+//! ```
+
+use super::{WorldGenerationParams, WorldType};
+use crate::province::generator::split_province_counts;
+use serde::Serialize;
+use std::fmt;
+
+/// Уровень серьёзности замечания
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Severity {
+    /// Генерация возможна, но результат может отличаться от ожидаемого
+    Warning,
+    /// Генерация с такими параметрами невозможна или некорректна
+    Error,
+}
+
+/// Замечание к конкретному полю конфигурации
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// Уровень серьёзности
+    pub severity: Severity,
+
+    /// Путь к полю в формате TOML (например, `"terrain.smooth_radius"`)
+    pub field: String,
+
+    /// Описание проблемы и допустимых значений
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(field: &str, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            field: field.to_string(),
+            message,
+        }
+    }
+
+    fn warning(field: &str, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            field: field.to_string(),
+            message,
+        }
+    }
+
+    /// Является ли замечание ошибкой (а не предупреждением)
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Warning => "предупреждение",
+            Severity::Error => "ошибка",
+        };
+        write!(f, "{level}: {}: {}", self.field, self.message)
+    }
+}
+
+/// Проверяет, что значение конечно и лежит в диапазоне `[min, max]`
+fn check_range(issues: &mut Vec<ValidationIssue>, field: &str, value: f32, min: f32, max: f32) {
+    if !value.is_finite() || value < min || value > max {
+        issues.push(ValidationIssue::error(
+            field,
+            format!("значение {value} вне допустимого диапазона [{min}, {max}]"),
+        ));
+    }
+}
+
+/// Проверяет, что значение конечно и строго положительно
+fn check_positive(issues: &mut Vec<ValidationIssue>, field: &str, value: f32) {
+    if !value.is_finite() || value <= 0.0 {
+        issues.push(ValidationIssue::error(
+            field,
+            format!("значение {value} должно быть положительным числом"),
+        ));
+    }
+}
+
+impl WorldGenerationParams {
+    /// Проверяет параметры генерации и возвращает список замечаний
+    ///
+    /// # Проверки
+    /// - **Размеры карты**: `width` и `height` больше нуля, `width × height` помещается в `u32`
+    /// - **Пресет**: для `Custom` задан `custom_preset` с корректными октавами, частотой и контрастом
+    /// - **Уровень моря**: `sea_level` и `target_land_ratio` в диапазоне `[0.0, 1.0]`
+    /// - **Рельеф**: `smooth_radius` меньше ширины и высоты карты, `elevation_power > 0`,
+    ///   `mountain_compression` в диапазоне `[0.0, 1.0]`
+    /// - **Острова**: `island_density` в диапазоне `[0.0, 1.0]`
+    /// - **Климат**: сдвиги температуры и влажности в диапазоне `[-1.0, 1.0]`,
+    ///   `polar_amplification ≥ 0`, `climate_latitude_exponent > 0`
+    /// - **Провинции**: `total_provinces ≥ 2` и провинции минимальной площади
+    ///   помещаются в ожидаемый бюджет пикселей суши и моря
+    /// - **Регионы**: `num_regions > 0`, `sea_province_scale > 0`
+    /// - **Площадь провинций**: `area.throttle ≥ 0`, для каждого типа провинции
+    ///   заданный минимум не больше максимума, а цель — между ними
+    ///
+    /// # Возвращает
+    /// Вектор замечаний. Пустой вектор означает, что параметры корректны.
+    /// Ошибки и предупреждения различаются полем [`ValidationIssue::severity`].
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::WorldGenerationParams;
+    ///
+    /// let params = WorldGenerationParams::default();
+    /// assert!(params.validate().iter().all(|issue| !issue.is_error()));
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        // === Размеры карты ===
+        if self.width == 0 {
+            issues.push(ValidationIssue::error(
+                "width",
+                "ширина карты должна быть больше нуля".to_string(),
+            ));
+        }
+        if self.height == 0 {
+            issues.push(ValidationIssue::error(
+                "height",
+                "высота карты должна быть больше нуля".to_string(),
+            ));
+        }
+        let total_pixels = u64::from(self.width) * u64::from(self.height);
+        if total_pixels > u64::from(u32::MAX) {
+            issues.push(ValidationIssue::error(
+                "width",
+                format!(
+                    "карта {}×{} слишком велика: число пикселей превышает {}",
+                    self.width,
+                    self.height,
+                    u32::MAX
+                ),
+            ));
+        }
+
+        // === Пресет мира ===
+        self.validate_preset(&mut issues);
+
+        // === Уровень моря и доля суши ===
+        check_range(&mut issues, "sea_level", self.sea_level, 0.0, 1.0);
+        if let Some(ratio) = self.target_land_ratio {
+            check_range(&mut issues, "target_land_ratio", ratio, 0.0, 1.0);
+        }
+
+        // === Рельеф ===
+        let smooth_radius = self.terrain.smooth_radius;
+        if self.width > 0
+            && self.height > 0
+            && (smooth_radius >= self.width as usize || smooth_radius >= self.height as usize)
+        {
+            issues.push(ValidationIssue::error(
+                "terrain.smooth_radius",
+                format!(
+                    "радиус сглаживания {smooth_radius} должен быть меньше размеров карты {}×{}",
+                    self.width, self.height
+                ),
+            ));
+        }
+        check_positive(
+            &mut issues,
+            "terrain.elevation_power",
+            self.terrain.elevation_power,
+        );
+        check_range(
+            &mut issues,
+            "terrain.mountain_compression",
+            self.terrain.mountain_compression,
+            0.0,
+            1.0,
+        );
+
+        // === Острова ===
+        check_range(
+            &mut issues,
+            "islands.island_density",
+            self.islands.island_density,
+            0.0,
+            1.0,
+        );
+        if total_pixels > 0 && u64::from(self.islands.min_island_size) > total_pixels {
+            issues.push(ValidationIssue::warning(
+                "islands.min_island_size",
+                format!(
+                    "минимальный размер острова {} больше площади карты ({total_pixels} пикселей)",
+                    self.islands.min_island_size
+                ),
+            ));
+        }
+
+        // === Климат ===
+        self.validate_climate(&mut issues);
+
+        // === Провинции и регионы ===
+        check_range(
+            &mut issues,
+            "merge.min_area_ratio",
+            self.merge.min_area_ratio,
+            0.0,
+            1.0,
+        );
+        self.validate_provinces(&mut issues, total_pixels);
+
+        if self.num_regions == 0 {
+            issues.push(ValidationIssue::error(
+                "num_regions",
+                "количество регионов должно быть больше нуля".to_string(),
+            ));
+        } else if self.num_regions > self.terrain.total_provinces {
+            issues.push(ValidationIssue::warning(
+                "num_regions",
+                format!(
+                    "регионов ({}) больше, чем провинций ({}): каждая провинция станет отдельным регионом",
+                    self.num_regions, self.terrain.total_provinces
+                ),
+            ));
+        }
+        check_positive(&mut issues, "sea_province_scale", self.sea_province_scale);
+        self.validate_area(&mut issues);
+
+        issues
+    }
+
+    /// Проверяет ограничения площади провинций
+    fn validate_area(&self, issues: &mut Vec<ValidationIssue>) {
+        if !self.area.throttle.is_finite() || self.area.throttle < 0.0 {
+            issues.push(ValidationIssue::error(
+                "area.throttle",
+                format!(
+                    "значение {} должно быть неотрицательным числом",
+                    self.area.throttle
+                ),
+            ));
+        }
+        for (name, limits) in [
+            ("continental", &self.area.continental),
+            ("island", &self.area.island),
+            ("oceanic", &self.area.oceanic),
+        ] {
+            if let (Some(min), Some(max)) = (limits.min, limits.max)
+                && min > max
+            {
+                issues.push(ValidationIssue::error(
+                    &format!("area.{name}.min"),
+                    format!("минимальная площадь {min} больше максимальной {max}"),
+                ));
+            }
+            if let Some(target) = limits.target
+                && (limits.min.is_some_and(|min| target < min)
+                    || limits.max.is_some_and(|max| target > max))
+            {
+                issues.push(ValidationIssue::warning(
+                    &format!("area.{name}.target"),
+                    format!("целевая площадь {target} вне заданных границ [min, max]"),
+                ));
+            }
+        }
+    }
+
+    /// Проверяет пользовательский пресет мира
+    fn validate_preset(&self, issues: &mut Vec<ValidationIssue>) {
+        let Some(preset) = &self.custom_preset else {
+            if self.world_type == WorldType::Custom {
+                issues.push(ValidationIssue::error(
+                    "custom_preset",
+                    "для world_type = \"Custom\" требуется секция [custom_preset] или preset из presets_file"
+                        .to_string(),
+                ));
+            }
+            return;
+        };
+        if self.world_type != WorldType::Custom {
+            issues.push(ValidationIssue::warning(
+                "custom_preset",
+                format!(
+                    "пресет игнорируется, так как тип мира {:?}, а не Custom",
+                    self.world_type
+                ),
+            ));
+            return;
+        }
+
+        check_range(
+            issues,
+            "custom_preset.land_ratio",
+            preset.land_ratio,
+            0.0,
+            1.0,
+        );
+        if preset.octaves < 1 {
+            issues.push(ValidationIssue::error(
+                "custom_preset.octaves",
+                format!(
+                    "количество октав {} должно быть не меньше 1",
+                    preset.octaves
+                ),
+            ));
+        }
+        check_positive(
+            issues,
+            "custom_preset.base_frequency",
+            preset.base_frequency,
+        );
+        check_positive(issues, "custom_preset.contrast", preset.contrast);
+    }
+
+    /// Проверяет климатические параметры
+    fn validate_climate(&self, issues: &mut Vec<ValidationIssue>) {
+        check_range(
+            issues,
+            "climate.global_temperature_offset",
+            self.climate.global_temperature_offset,
+            -1.0,
+            1.0,
+        );
+        check_range(
+            issues,
+            "climate.global_humidity_offset",
+            self.climate.global_humidity_offset,
+            -1.0,
+            1.0,
+        );
+        if !self.climate.polar_amplification.is_finite() || self.climate.polar_amplification < 0.0 {
+            issues.push(ValidationIssue::error(
+                "climate.polar_amplification",
+                format!(
+                    "значение {} должно быть неотрицательным числом",
+                    self.climate.polar_amplification
+                ),
+            ));
+        }
+        check_positive(
+            issues,
+            "climate.climate_latitude_exponent",
+            self.climate.climate_latitude_exponent,
+        );
+    }
+
+    /// Проверяет количество провинций относительно бюджета пикселей
+    ///
+    /// Бюджет суши оценивается по целевой доле суши (`land_ratio`), так как
+    /// фактическая карта высот ещё не сгенерирована.
+    fn validate_provinces(&self, issues: &mut Vec<ValidationIssue>, total_pixels: u64) {
+        let total_provinces = self.terrain.total_provinces;
+        if total_provinces < 2 {
+            issues.push(ValidationIssue::error(
+                "terrain.total_provinces",
+                format!(
+                    "требуется минимум 2 провинции (одна сухопутная и одна морская), указано {total_provinces}"
+                ),
+            ));
+            return;
+        }
+        if total_pixels == 0 {
+            return;
+        }
+
+        let min_area = self.merge.threshold(self.width, self.height) as u64;
+        if total_provinces as u64 * min_area > total_pixels {
+            issues.push(ValidationIssue::error(
+                "terrain.total_provinces",
+                format!(
+                    "{total_provinces} провинций по {min_area} пикселей не помещаются на карту из {total_pixels} пикселей \
+                     (максимум {})",
+                    total_pixels / min_area
+                ),
+            ));
+            return;
+        }
+
+        let land_ratio = f64::from(self.land_ratio().clamp(0.0, 1.0));
+        let land_budget = (total_pixels as f64 * land_ratio) as u64;
+        let sea_budget = total_pixels - land_budget;
+        let (expected_land, expected_sea) = split_province_counts(
+            land_budget as usize,
+            sea_budget as usize,
+            total_provinces,
+            self.sea_province_scale,
+        );
+        let (expected_land, expected_sea) = (expected_land as u64, expected_sea as u64);
+        if expected_land * min_area > land_budget {
+            issues.push(ValidationIssue::warning(
+                "terrain.total_provinces",
+                format!(
+                    "ожидается ~{land_budget} пикселей суши: {expected_land} сухопутных провинций \
+                     окажутся меньше {min_area} пикселей и будут слиты"
+                ),
+            ));
+        }
+        if expected_sea * min_area > sea_budget {
+            issues.push(ValidationIssue::warning(
+                "terrain.total_provinces",
+                format!(
+                    "ожидается ~{sea_budget} пикселей моря: {expected_sea} морских провинций \
+                     окажутся меньше {min_area} пикселей и будут слиты"
+                ),
+            ));
+        }
+    }
+}
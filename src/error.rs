@@ -0,0 +1,249 @@
+// src/error.rs
+//! Ошибки генератора мира
+//!
+//! Этот модуль определяет единый тип ошибки [`MapgenError`] для всех операций библиотеки,
+//! которые могут завершиться неудачей:
+//! - Загрузка и разбор конфигурации (с позицией ошибки в TOML-файле)
+//! - Проверка параметров генерации
+//! - Создание изображений с некорректными размерами
+//! - Кодирование изображений
+//! - Чтение файлов данных (сырые карты высот и их описания)
+//! - Операции ввода-вывода
+//!
+//! Типизированные варианты позволяют вызывающему коду реагировать на ошибку
+//! программно, без сопоставления строк.
+
+use crate::config::ValidationIssue;
+use crate::stage::Stage;
+use image::{ImageBuffer, Pixel};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Ошибка генератора мира
+#[derive(Debug)]
+pub enum MapgenError {
+    /// Ошибка ввода-вывода при чтении или записи файла
+    Io {
+        /// Путь к файлу, с которым выполнялась операция
+        path: PathBuf,
+        /// Исходная ошибка ввода-вывода
+        source: io::Error,
+    },
+
+    /// Ошибка разбора конфигурации в формате TOML
+    ConfigParse {
+        /// Путь к файлу конфигурации (`None`, если разбиралась строка)
+        path: Option<PathBuf>,
+        /// Описание ошибки от парсера
+        message: String,
+        /// Номер строки (с 1), если парсер сообщил позицию ошибки
+        line: Option<usize>,
+        /// Номер столбца (с 1), если парсер сообщил позицию ошибки
+        column: Option<usize>,
+    },
+
+    /// Параметры генерации не прошли проверку (`WorldGenerationParams::validate`)
+    InvalidConfig {
+        /// Замечания с уровнем `Severity::Error`
+        issues: Vec<ValidationIssue>,
+    },
+
+    /// Пресет мира с указанным именем не найден в библиотеке пресетов
+    UnknownPreset {
+        /// Запрошенное имя пресета
+        name: String,
+        /// Имена пресетов, доступных в библиотеке
+        available: Vec<String>,
+    },
+
+    /// Неизвестное имя этапа генерации
+    UnknownStage {
+        /// Запрошенное имя этапа
+        name: String,
+    },
+
+    /// Размер буфера данных не соответствует размерам карты
+    InvalidDimensions {
+        /// Ширина карты в пикселях
+        width: u32,
+        /// Высота карты в пикселях
+        height: u32,
+        /// Ожидаемая длина буфера (`width × height × каналы`)
+        expected: usize,
+        /// Фактическая длина буфера
+        actual: usize,
+    },
+
+    /// Файл данных имеет недопустимый формат или содержимое
+    InvalidData {
+        /// Путь к файлу данных (`None`, если разбирались данные в памяти)
+        path: Option<PathBuf>,
+        /// Описание проблемы
+        message: String,
+    },
+
+    /// Ошибка кодирования изображения
+    ImageEncoding {
+        /// Путь к файлу изображения
+        path: PathBuf,
+        /// Исходная ошибка библиотеки `image`
+        source: image::ImageError,
+    },
+}
+
+impl MapgenError {
+    /// Создаёт ошибку разбора конфигурации из ошибки `toml`
+    ///
+    /// Байтовое смещение ошибки переводится в номер строки и столбца по исходному тексту.
+    pub(crate) fn config_parse(
+        path: Option<&Path>,
+        contents: &str,
+        source: &toml::de::Error,
+    ) -> Self {
+        let (line, column) = match source.span() {
+            Some(span) => {
+                let (line, column) = line_column(contents, span.start);
+                (Some(line), Some(column))
+            }
+            None => (None, None),
+        };
+        Self::ConfigParse {
+            path: path.map(Path::to_path_buf),
+            message: source.message().to_string(),
+            line,
+            column,
+        }
+    }
+
+    /// Создаёт ошибку из ошибки сохранения изображения
+    ///
+    /// Ошибки ввода-вывода внутри `image::ImageError` превращаются в [`MapgenError::Io`].
+    pub(crate) fn image(path: &str, source: image::ImageError) -> Self {
+        match source {
+            image::ImageError::IoError(source) => Self::Io {
+                path: PathBuf::from(path),
+                source,
+            },
+            source => Self::ImageEncoding {
+                path: PathBuf::from(path),
+                source,
+            },
+        }
+    }
+}
+
+impl fmt::Display for MapgenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => {
+                write!(f, "ошибка ввода-вывода ({}): {source}", path.display())
+            }
+            Self::ConfigParse {
+                path,
+                message,
+                line,
+                column,
+            } => {
+                write!(f, "ошибка разбора конфигурации")?;
+                if let Some(path) = path {
+                    write!(f, " {}", path.display())?;
+                }
+                if let (Some(line), Some(column)) = (line, column) {
+                    write!(f, " (строка {line}, столбец {column})")?;
+                }
+                write!(f, ": {}", message.trim_end())
+            }
+            Self::InvalidConfig { issues } => {
+                write!(f, "некорректная конфигурация")?;
+                for issue in issues {
+                    write!(f, "\n  - {issue}")?;
+                }
+                Ok(())
+            }
+            Self::UnknownPreset { name, available } => {
+                write!(f, "пресет мира «{name}» не найден")?;
+                if available.is_empty() {
+                    write!(f, " (не указан файл пресетов presets_file)")
+                } else {
+                    write!(f, "; доступные пресеты: {}", available.join(", "))
+                }
+            }
+            Self::UnknownStage { name } => {
+                let names: Vec<&str> = Stage::ALL.iter().map(|s| s.name()).collect();
+                write!(
+                    f,
+                    "неизвестный этап «{name}»; доступные этапы: {}",
+                    names.join(", ")
+                )
+            }
+            Self::InvalidDimensions {
+                width,
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "некорректные размеры карты {width}×{height}: ожидалось {expected} элементов, получено {actual}"
+            ),
+            Self::InvalidData { path, message } => {
+                write!(f, "некорректные данные")?;
+                if let Some(path) = path {
+                    write!(f, " ({})", path.display())?;
+                }
+                write!(f, ": {message}")
+            }
+            Self::ImageEncoding { path, source } => {
+                write!(
+                    f,
+                    "ошибка кодирования изображения ({}): {source}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapgenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::ImageEncoding { source, .. } => Some(source),
+            Self::ConfigParse { .. }
+            | Self::InvalidConfig { .. }
+            | Self::UnknownPreset { .. }
+            | Self::UnknownStage { .. }
+            | Self::InvalidDimensions { .. }
+            | Self::InvalidData { .. } => None,
+        }
+    }
+}
+
+/// Создаёт буфер изображения с проверкой размеров
+///
+/// В отличие от `ImageBuffer::from_raw`, возвращает [`MapgenError::InvalidDimensions`]
+/// с ожидаемой и фактической длиной буфера вместо `None`.
+pub(crate) fn image_buffer<P: Pixel>(
+    width: u32,
+    height: u32,
+    data: Vec<P::Subpixel>,
+) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, MapgenError> {
+    let expected = width as usize * height as usize * usize::from(P::CHANNEL_COUNT);
+    let actual = data.len();
+    ImageBuffer::from_raw(width, height, data).ok_or(MapgenError::InvalidDimensions {
+        width,
+        height,
+        expected,
+        actual,
+    })
+}
+
+/// Переводит байтовое смещение в тексте в пару `(строка, столбец)`, считая с 1
+fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = contents.get(..offset).unwrap_or(contents);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(before.chars().count(), |pos| {
+        before[pos + 1..].chars().count()
+    }) + 1;
+    (line, column)
+}
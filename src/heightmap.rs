@@ -12,7 +12,7 @@
 //! 1. **Базовый шум (3D для бесшовности)**:
 //!    - Используется 3D-шум `OpenSimplex2` для создания бесшовной карты по долготе
 //!    - Цилиндрическая проекция: `(x, y)` → `(radius*cos(angle), y, radius*sin(angle))`
-//!    - Адаптивные параметры октав и частоты в зависимости от типа мира
+//!    - Параметры октав, частоты и контраста берутся из пресета мира (`WorldPreset`)
 //!
 //! 2. **Добавление островов**:
 //!    - Отдельный генератор шума для мелких островов в океанах
@@ -35,6 +35,15 @@
 //! 6. **Нормализация**:
 //!    - Линейная нормализация в диапазон [0.0, 1.0]
 //!    - Подбор смещения для достижения целевой доли суши (`target_land_ratio`)
+//!      относительно заданного уровня моря (`sea_level`)
+//!
+//! ## Экспорт и загрузка
+//!
+//! - **8-битный PNG** (`save_as_png`) — для быстрого просмотра
+//! - **16-битный PNG** (`save_as_png16`) — без заметной ступенчатости рельефа
+//! - **Сырые `f32`** (`save_raw`) — `.r32` + JSON-описание, точность без потерь
+//!
+//! Все форматы читаются обратно через `load_png` и `load_raw`.
 //!
 //! ## Особенности реализации
 //!
@@ -43,10 +52,14 @@
 //! - **Эффективность**: параллельная обработка при включённой фиче `parallel`
 //! - **Физическая достоверность**: эрозия моделирует реальные геоморфологические процессы
 
-use crate::config::{TerrainSettings, WorldType};
+use crate::config::{TerrainSettings, WorldPreset};
+use crate::error::{MapgenError, image_buffer};
 use fastnoise_lite::{FastNoiseLite, FractalType, NoiseType};
-use image::{ImageBuffer, Luma};
+use image::{ColorType, ImageBuffer, Luma};
 use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -55,7 +68,7 @@ use rayon::prelude::*;
 ///
 /// Карта представляет собой плоский вектор данных размером `ширина × высота`,
 /// где каждый элемент — нормализованная высота пикселя.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Heightmap {
     /// Ширина карты в пикселях
     pub width: u32,
@@ -72,6 +85,35 @@ pub struct Heightmap {
     pub data: Vec<f32>,
 }
 
+/// Идентификатор формата сырой карты высот в JSON-описании
+const RAW_FORMAT: &str = "f32le";
+
+/// JSON-описание сырой карты высот (см. [`Heightmap::save_raw`])
+///
+/// Пример содержимого `heightmap.json`:
+/// ```json
+/// { "format": "f32le", "width": 2048, "height": 1024, "sea_level": 0.5 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawHeightmapInfo {
+    /// Формат данных: всегда `"f32le"` (32-bit float, little-endian)
+    pub format: String,
+
+    /// Ширина карты в пикселях
+    pub width: u32,
+
+    /// Высота карты в пикселях
+    pub height: u32,
+
+    /// Уровень моря, с которым была сгенерирована карта
+    pub sea_level: f32,
+}
+
+/// Путь к JSON-описанию сырой карты высот: тот же путь с расширением `.json`
+fn raw_sidecar_path(path: &str) -> PathBuf {
+    Path::new(path).with_extension("json")
+}
+
 impl Heightmap {
     /// Создаёт новую пустую карту высот заданных размеров
     ///
@@ -168,18 +210,234 @@ impl Heightmap {
     /// * `path` — путь к файлу для сохранения
     ///
     /// # Ошибки
-    /// Возвращает ошибку, если не удаётся создать или записать файл.
+    /// - [`MapgenError::InvalidDimensions`] — размер данных не совпадает с размерами карты
+    /// - [`MapgenError::ImageEncoding`] — не удалось закодировать изображение
+    /// - [`MapgenError::Io`] — не удалось записать файл
     ///
     /// # Пример
     /// ```rust
     /// map.save_as_png("output/heightmap.png")?;
     /// ```
-    pub fn save_as_png(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save_as_png(&self, path: &str) -> Result<(), MapgenError> {
         let img: ImageBuffer<Luma<u8>, Vec<u8>> =
-            ImageBuffer::from_raw(self.width, self.height, self.to_grayscale_image())
-                .ok_or("Failed to create image buffer")?;
-        img.save(path)?;
-        Ok(())
+            image_buffer(self.width, self.height, self.to_grayscale_image())?;
+        img.save(path).map_err(|e| MapgenError::image(path, e))
+    }
+
+    /// Преобразует карту высот в 16-битные градации серого
+    ///
+    /// В отличие от [`Heightmap::to_grayscale_image`], сохраняет 65536 уровней высоты,
+    /// что устраняет ступенчатость рельефа при импорте в 3D-движок:
+    /// ```text
+    /// яркость = высота × 65535
+    /// ```
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::heightmap::Heightmap;
+    ///
+    /// let mut map = Heightmap::new(2, 1);
+    /// map.set(1, 0, 0.5);
+    /// assert_eq!(map.to_grayscale_image16(), vec![0, 32767]);
+    /// ```
+    #[must_use]
+    pub fn to_grayscale_image16(&self) -> Vec<u16> {
+        #[cfg(feature = "parallel")]
+        {
+            self.data
+                .par_iter()
+                .map(|&v| (v.clamp(0.0, 1.0) * 65535.0) as u16)
+                .collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            self.data
+                .iter()
+                .map(|&v| (v.clamp(0.0, 1.0) * 65535.0) as u16)
+                .collect()
+        }
+    }
+
+    /// Сохраняет карту высот в 16-битный монохромный PNG-файл
+    ///
+    /// Файл читается обратно через [`Heightmap::load_png`] с точностью `1/65535`.
+    ///
+    /// # Параметры
+    /// * `path` — путь к файлу для сохранения
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::InvalidDimensions`] — размер данных не совпадает с размерами карты
+    /// - [`MapgenError::ImageEncoding`] — не удалось закодировать изображение
+    /// - [`MapgenError::Io`] — не удалось записать файл
+    pub fn save_as_png16(&self, path: &str) -> Result<(), MapgenError> {
+        let img: ImageBuffer<Luma<u16>, Vec<u16>> =
+            image_buffer(self.width, self.height, self.to_grayscale_image16())?;
+        img.save(path).map_err(|e| MapgenError::image(path, e))
+    }
+
+    /// Загружает карту высот из изображения (например, отредактированного `heightmap.png`)
+    ///
+    /// Поддерживаются монохромные и цветные изображения любой разрядности,
+    /// которые умеет декодировать библиотека `image`:
+    /// - **8 и 16 бит на канал** — значения делятся на максимум канала (255 или 65535)
+    /// - **Вещественные** (32-bit float, например TIFF или EXR) — значения берутся как есть;
+    ///   если они выходят за пределы `[0.0, 1.0]`, карта линейно нормализуется
+    ///
+    /// Для цветных изображений высотой считается яркость пикселя.
+    ///
+    /// # Параметры
+    /// * `path` — путь к файлу изображения
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — файл не найден или не может быть прочитан
+    /// - [`MapgenError::ImageEncoding`] — файл не является поддерживаемым изображением
+    ///
+    /// # Пример
+    /// ```rust,no_run
+    /// use mapgen::heightmap::Heightmap;
+    ///
+    /// let map = Heightmap::load_png("output/heightmap.png")?;
+    /// assert!(map.data.iter().all(|h| (0.0..=1.0).contains(h)));
+    /// # Ok::<(), mapgen::MapgenError>(())
+    /// ```
+    pub fn load_png(path: &str) -> Result<Self, MapgenError> {
+        let img = image::open(path).map_err(|e| MapgenError::image(path, e))?;
+        let is_float = matches!(img.color(), ColorType::Rgb32F | ColorType::Rgba32F);
+        let luma = img.to_luma32f();
+        let mut map = Self {
+            width: luma.width(),
+            height: luma.height(),
+            data: luma.into_raw(),
+        };
+
+        // Вещественные карты могут хранить высоты в произвольных единицах
+        if is_float && map.data.iter().any(|h| !(0.0..=1.0).contains(h)) {
+            let (min, max) = map
+                .data
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
+            let range = (max - min).max(f32::EPSILON);
+            for h in &mut map.data {
+                *h = (*h - min) / range;
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Сохраняет карту высот без потери точности: сырые `f32` + JSON-описание
+    ///
+    /// Создаёт два файла:
+    /// - `path` (например, `heightmap.r32`) — значения `f32` в порядке little-endian,
+    ///   построчно, без заголовка (формат «RAW 32-bit float» редакторов рельефа)
+    /// - `path` с расширением `.json` — описание [`RawHeightmapInfo`]
+    ///   (размеры и уровень моря)
+    ///
+    /// # Параметры
+    /// * `path` — путь к файлу данных
+    /// * `sea_level` — уровень моря, с которым была сгенерирована карта
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — не удалось записать файл данных или описания
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::heightmap::Heightmap;
+    ///
+    /// let mut map = Heightmap::new(4, 2);
+    /// map.set(3, 1, 0.123_456_7);
+    ///
+    /// let path = std::env::temp_dir().join("mapgen_doc_heightmap.r32");
+    /// map.save_raw(path.to_str().unwrap(), 0.45)?;
+    ///
+    /// let (loaded, info) = Heightmap::load_raw(path.to_str().unwrap())?;
+    /// assert_eq!(loaded.data, map.data);
+    /// assert_eq!((info.width, info.height, info.sea_level), (4, 2, 0.45));
+    /// # Ok::<(), mapgen::MapgenError>(())
+    /// ```
+    pub fn save_raw(&self, path: &str, sea_level: f32) -> Result<(), MapgenError> {
+        let bytes: Vec<u8> = self.data.iter().flat_map(|v| v.to_le_bytes()).collect();
+        fs::write(path, bytes).map_err(|source| MapgenError::Io {
+            path: PathBuf::from(path),
+            source,
+        })?;
+
+        let info = RawHeightmapInfo {
+            format: RAW_FORMAT.to_string(),
+            width: self.width,
+            height: self.height,
+            sea_level,
+        };
+        let sidecar = raw_sidecar_path(path);
+        let json = serde_json::to_string_pretty(&info).map_err(|e| MapgenError::InvalidData {
+            path: Some(sidecar.clone()),
+            message: e.to_string(),
+        })?;
+        fs::write(&sidecar, json).map_err(|source| MapgenError::Io {
+            path: sidecar,
+            source,
+        })
+    }
+
+    /// Загружает карту высот, сохранённую [`Heightmap::save_raw`]
+    ///
+    /// Размеры берутся из JSON-описания рядом с файлом данных
+    /// (тот же путь с расширением `.json`).
+    ///
+    /// # Возвращает
+    /// Кортеж `(карта высот, описание)`.
+    ///
+    /// # Ошибки
+    /// - [`MapgenError::Io`] — файл данных или описания не может быть прочитан
+    /// - [`MapgenError::InvalidData`] — описание повреждено или имеет неизвестный формат
+    /// - [`MapgenError::InvalidDimensions`] — размер данных не совпадает с описанием
+    pub fn load_raw(path: &str) -> Result<(Self, RawHeightmapInfo), MapgenError> {
+        let sidecar = raw_sidecar_path(path);
+        let json = fs::read_to_string(&sidecar).map_err(|source| MapgenError::Io {
+            path: sidecar.clone(),
+            source,
+        })?;
+        let info: RawHeightmapInfo =
+            serde_json::from_str(&json).map_err(|e| MapgenError::InvalidData {
+                path: Some(sidecar.clone()),
+                message: e.to_string(),
+            })?;
+        if info.format != RAW_FORMAT {
+            return Err(MapgenError::InvalidData {
+                path: Some(sidecar),
+                message: format!(
+                    "неподдерживаемый формат «{}», ожидается «{RAW_FORMAT}»",
+                    info.format
+                ),
+            });
+        }
+
+        let bytes = fs::read(path).map_err(|source| MapgenError::Io {
+            path: PathBuf::from(path),
+            source,
+        })?;
+        let expected = info.width as usize * info.height as usize;
+        if bytes.len() != expected * 4 {
+            return Err(MapgenError::InvalidDimensions {
+                width: info.width,
+                height: info.height,
+                expected,
+                actual: bytes.len() / 4,
+            });
+        }
+        let data = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+
+        Ok((
+            Self {
+                width: info.width,
+                height: info.height,
+                data,
+            },
+            info,
+        ))
     }
 
     /// Применяет термальную эрозию (гравитационное выветривание)
@@ -320,6 +578,83 @@ impl Heightmap {
         }
     }
 
+    /// Удаляет острова площадью меньше `min_size` пикселей
+    ///
+    /// Мелкие массивы суши опускаются чуть ниже уровня моря и становятся отмелями
+    /// окружающего водоёма. Связность определяется по 4 направлениям
+    /// с учётом бесшовности по долготе.
+    ///
+    /// # Параметры
+    /// * `sea_level` — уровень моря, пиксели не ниже него считаются сушей
+    /// * `min_size` — минимальная площадь острова в пикселях
+    ///
+    /// # Возвращает
+    /// Количество удалённых островов.
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::heightmap::Heightmap;
+    ///
+    /// let mut map = Heightmap::new(16, 16);
+    /// map.set(3, 3, 0.8); // одиночный пиксель суши
+    /// for x in 8..14 {
+    ///     for y in 8..14 {
+    ///         map.set(x, y, 0.8); // остров 6×6
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(map.remove_small_islands(0.5, 10), 1);
+    /// assert!(map.get(3, 3) < 0.5);
+    /// assert!(map.get(10, 10) >= 0.5);
+    /// ```
+    pub fn remove_small_islands(&mut self, sea_level: f32, min_size: usize) -> usize {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let shoal_height = (sea_level - 0.01).max(0.0);
+
+        let mut visited = vec![false; self.data.len()];
+        let mut component = Vec::new();
+        let mut removed = 0;
+
+        for start in 0..self.data.len() {
+            if visited[start] || self.data[start] < sea_level {
+                continue;
+            }
+
+            // BFS по связной компоненте суши
+            component.clear();
+            visited[start] = true;
+            let mut queue = std::collections::VecDeque::from([start]);
+            while let Some(idx) = queue.pop_front() {
+                component.push(idx);
+                let x = (idx % width) as i32;
+                let y = (idx / width) as i32;
+                for &(dx, dy) in &[(0, 1), (1, 0), (0, -1), (-1, 0)] {
+                    // X зацикливаем по долготе, Y ограничиваем по широте
+                    let nx = (x + dx).rem_euclid(width as i32) as usize;
+                    let ny = y + dy;
+                    if ny < 0 || ny >= height as i32 {
+                        continue;
+                    }
+                    let nidx = ny as usize * width + nx;
+                    if !visited[nidx] && self.data[nidx] >= sea_level {
+                        visited[nidx] = true;
+                        queue.push_back(nidx);
+                    }
+                }
+            }
+
+            if component.len() < min_size {
+                for &idx in &component {
+                    self.data[idx] = shoal_height;
+                }
+                removed += 1;
+            }
+        }
+
+        removed
+    }
+
     /// Генерирует карту нормалей из карты высот
     ///
     /// Нормали используются для шейдинга в 3D-рендере или для вычисления освещения.
@@ -400,23 +735,23 @@ impl Heightmap {
     /// * `path` — путь к файлу для сохранения
     ///
     /// # Ошибки
-    /// Возвращает ошибку, если не удаётся создать или записать файл.
+    /// - [`MapgenError::InvalidDimensions`] — размер данных не совпадает с размерами карты
+    /// - [`MapgenError::ImageEncoding`] — не удалось закодировать изображение
+    /// - [`MapgenError::Io`] — не удалось записать файл
     ///
     /// # Пример
     /// ```rust
     /// map.save_normals_as_png("output/normals.png")?;
     /// ```
-    pub fn save_normals_as_png(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        use image::{ImageBuffer, Rgb};
+    pub fn save_normals_as_png(&self, path: &str) -> Result<(), MapgenError> {
+        use image::Rgb;
         let normals = self.generate_normals();
-        let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_raw(
+        let img: ImageBuffer<Rgb<u8>, Vec<u8>> = image_buffer(
             self.width,
             self.height,
             normals.into_iter().flatten().collect(),
-        )
-        .ok_or("Failed to create normals image buffer")?;
-        img.save(path)?;
-        Ok(())
+        )?;
+        img.save(path).map_err(|e| MapgenError::image(path, e))
     }
 }
 
@@ -433,8 +768,10 @@ impl Heightmap {
 /// # Параметры
 /// * `seed` — сид для детерминированной генерации
 /// * `width`, `height` — размеры карты в пикселях
-/// * `world_type` — тип генерируемого мира (влияет на параметры шума и эрозии)
+/// * `preset` — пресет мира (октавы, базовая частота и кривая контраста шума)
 /// * `island_density` — плотность мелких островов в океанах (0.0–1.0)
+/// * `sea_level` — уровень моря, относительно которого подбирается доля суши
+/// * `target_land_ratio` — целевая доля суши (обычно `WorldType::target_land_ratio`)
 /// * `terrain` — настройки рельефа (сглаживание, экспонента высоты)
 ///
 /// # Возвращает
@@ -446,23 +783,26 @@ impl Heightmap {
 ///     42,
 ///     2048,
 ///     1024,
-///     WorldType::EarthLike,
+///     &WorldType::EarthLike.preset(),
 ///     0.2,
+///     0.5,
+///     WorldType::EarthLike.target_land_ratio(),
 ///     &TerrainSettings::default(),
 /// );
 /// ```
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 #[must_use]
 pub fn generate_heightmap(
     seed: u64,
     width: u32,
     height: u32,
-    world_type: WorldType,
+    preset: &WorldPreset,
     island_density: f32,
+    sea_level: f32,
+    target_land_ratio: f32,
     terrain: &TerrainSettings,
 ) -> Heightmap {
     let width_f = width as f32;
-    let target_land_ratio = world_type.target_land_ratio();
 
     // Параметры для цилиндрической проекции (радиус цилиндра)
     let radius = width_f / (2.0 * std::f32::consts::PI);
@@ -473,31 +813,20 @@ pub fn generate_heightmap(
     noise.set_noise_type(Some(NoiseType::OpenSimplex2));
     noise.set_fractal_type(Some(FractalType::FBm));
 
-    // Адаптируем октавы под тип мира
-    let octaves = match world_type {
-        WorldType::Supercontinent | WorldType::Mediterranean => 3,
-        WorldType::Archipelago => 4,
-        _ => 5,
-    };
-    noise.set_fractal_octaves(Some(octaves));
-
-    // Частота: крупные формы для континентов, мелкие для архипелагов
-    let base_frequency = match world_type {
-        WorldType::Supercontinent | WorldType::Mediterranean => 0.002,
-        _ => 0.005,
-    };
-    noise.set_frequency(Some(base_frequency));
+    // Октавы и частота из пресета: крупные формы для континентов, мелкие для архипелагов
+    noise.set_fractal_octaves(Some(preset.octaves));
+    noise.set_frequency(Some(preset.base_frequency));
 
     // Генерация базовой карты высот
     #[cfg(feature = "parallel")]
     let mut data: Vec<f32> = (0..(width * height))
         .into_par_iter()
-        .map(|i| generate_height_value(i, width, &noise, world_type, radius))
+        .map(|i| generate_height_value(i, width, &noise, preset.contrast, radius))
         .collect();
 
     #[cfg(not(feature = "parallel"))]
     let mut data: Vec<f32> = (0..(width * height))
-        .map(|i| generate_height_value(i, width, &noise, world_type, radius))
+        .map(|i| generate_height_value(i, width, &noise, preset.contrast, radius))
         .collect();
 
     // === 2. Добавление островов (До эрозии!) ===
@@ -567,15 +896,15 @@ pub fn generate_heightmap(
         }
     }
 
-    // Подбор сдвига для достижения целевой доли суши
+    // Подбор сдвига для достижения целевой доли суши относительно уровня моря
     let mut best_offset = 0.0;
     let mut best_diff = f32::INFINITY;
     for i in 0..100 {
-        let offset = (i as f32) / 100.0 - 0.5;
+        let offset = (i as f32) / 100.0 - 1.0 + sea_level;
         let land_count = heightmap
             .data
             .iter()
-            .filter(|&&h| (h + offset).clamp(0.0, 1.0) > 0.5)
+            .filter(|&&h| (h + offset).clamp(0.0, 1.0) > sea_level)
             .count();
         let land_ratio = land_count as f32 / heightmap.data.len() as f32;
         let diff = (land_ratio - target_land_ratio).abs();
@@ -598,7 +927,7 @@ pub fn generate_heightmap(
 /// * `i` — линейный индекс пикселя (0..ширина×высота)
 /// * `width` — ширина карты в пикселях
 /// * `noise` — генератор шума с настроенными параметрами
-/// * `world_type` — тип мира (влияет на постобработку)
+/// * `contrast` — кривая контраста пресета (степень базового шума)
 /// * `radius` — радиус цилиндра для проекции
 ///
 /// # Возвращает
@@ -607,7 +936,7 @@ fn generate_height_value(
     i: u32,
     width: u32,
     noise: &FastNoiseLite,
-    world_type: WorldType,
+    contrast: f32,
     radius: f32,
 ) -> f32 {
     let x = (i % width) as f32;
@@ -622,9 +951,9 @@ fn generate_height_value(
     let mut value = noise.get_noise_3d(nx, ny, nz);
     value = (value + 1.0) * 0.5;
 
-    // Для архипелагов усиливаем контраст для создания множества островов
-    if matches!(world_type, WorldType::Archipelago) {
-        value = value * value;
+    // Усиление контраста (например, для архипелагов) создаёт множество островов
+    if (contrast - 1.0).abs() > f32::EPSILON {
+        value = value.powf(contrast);
     }
     value
 }
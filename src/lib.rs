@@ -5,23 +5,37 @@
 //! - Climate and biomes
 //! - Provinces and regions
 //! - Rivers and strategic points
+//! - Pathfinding over the pixel grid and the province graph
+//! - Sea-lane network between ports
 //!
 //! All functions are deterministic and seed-based.
+//! The whole pipeline is available as [`generate_world`]; a generated [`World`]
+//! can be saved to and loaded from a single versioned file ([`bundle`]).
 //! For CLI usage, see the `mapgen-cli` binary.
 pub mod biome;
+pub mod bundle;
 pub mod climate;
 pub mod config;
+pub mod error;
 pub mod heightmap;
+pub mod naval;
+pub mod pathfinding;
 pub mod province;
 pub mod region;
 pub mod rivers;
+pub mod stage;
 pub mod strategic;
+pub mod world;
 
 // === Конфигурация ===
 pub use config::{
-    ClimateSettings, IslandSettings, TerrainSettings, WorldGenerationParams, WorldType,
+    ClimateSettings, IslandSettings, Severity, TerrainSettings, ValidationIssue,
+    WorldGenerationParams, WorldType,
 };
 
+// === Ошибки ===
+pub use error::MapgenError;
+
 // === Карта высот ===
 pub use heightmap::{Heightmap, generate_heightmap};
 
@@ -38,7 +52,19 @@ pub use province::water::{WaterType, classify_water};
 pub use province::{
     Province, ProvinceType,
     generator::{generate_province_seeds, generate_provinces_from_seeds},
-    graph::build_province_graph_with_map,
+    area::{
+        AreaBalance, AreaLimits, AreaRange, AreaReport, AreaSettings, AreaStats, AreaTargets,
+        balance_province_areas,
+    },
+    growth::{
+        GrowthMode, GrowthSettings, generate_provinces_balanced, generate_provinces_cost_weighted,
+        grow_provinces,
+    },
+    seeding::{SeedingMode, SeedingSettings, generate_relaxed_seeds},
+    graph::{
+        AdjacencyEdge, BorderKind, ProvinceBorder, ProvinceGraph, adjacency_list,
+        build_province_graph_with_map, neighbor_ids,
+    },
 };
 
 // === Регионы ===
@@ -47,5 +73,18 @@ pub use region::{Region, group_provinces_into_regions};
 // === Реки ===
 pub use rivers::{RiverMap, generate_rivers};
 
+// === Поиск путей ===
+pub use pathfinding::{MovementCosts, MovementMode, PathGrid, find_province_path};
+
+// === Морские пути ===
+pub use naval::{PortRoute, SeaLane, SeaLaneNetwork, build_sea_lanes};
+
 // === Стратегические точки ===
 pub use strategic::{StrategicPoint, find_strategic_points};
+
+// === Полный конвейер ===
+pub use stage::Stage;
+pub use world::{
+    PartialWorld, World, generate_partial_world, generate_partial_world_from_heightmap,
+    generate_world,
+};
@@ -0,0 +1,448 @@
+// src/naval.rs
+//! Сеть морских путей
+//!
+//! Этот модуль строит навигационную сеть флота поверх морских провинций:
+//! - **Акватории** ([`SeaArea`]) — морские провинции с глубиной, ледовитостью
+//!   и средней стоимостью плавания
+//! - **Морские пути** ([`SeaLane`]) — переходы между соседними морскими провинциями
+//!   с расстоянием, стоимостью и признаком блокировки льдом
+//! - **Маршруты между портами** ([`PortRoute`]) — кратчайшие по стоимости пути
+//!   от каждого порта (порты и устья из [`find_strategic_points`]) до ближайших портов
+//!
+//! ## Алгоритм
+//!
+//! 1. **Акватории**: за один проход по карте для каждой морской провинции считаются
+//!    доли глубоководных (`DeepOcean`) и замёрзших (`FrozenOcean`) пикселей океана
+//!    и средняя [`Biome::movement_cost`] её пикселей
+//! 2. **Морские пути**: каждое ребро `SeaSea` графа провинций становится путём:
+//!    - Расстояние — между центрами провинций (с учётом бесшовности по долготе)
+//!    - Стоимость — расстояние × среднее стоимостей плавания двух акваторий
+//!      (глубокая вода, `IcyOcean` и `FrozenOcean` дороже мелководья)
+//!    - Путь заблокирован льдом, если хотя бы одна акватория замёрзла
+//!      (доля `FrozenOcean` ≥ [`ICE_BLOCKED_FRACTION`])
+//! 3. **Маршруты**: из каждого порта запускается алгоритм Дейкстры по незаблокированным
+//!    путям; порт выходит в море через соседние по графу акватории (рёбра `LandSea`).
+//!    Каждый порт соединяется с [`MAX_ROUTES_PER_PORT`] ближайшими достижимыми портами
+//!
+//! ## Особенности
+//!
+//! - Озёра в сеть не входят: учитываются только провинции, растущие из океана
+//! - Маршрут между парой портов выдаётся один раз (меньший `province_id` первым)
+//! - **Детерминированность**: результат одинаков для одинаковых входных данных
+//!
+//! [`find_strategic_points`]: crate::strategic::find_strategic_points
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::{WorldGenerationParams, generate_world};
+//!
+//! let params = WorldGenerationParams {
+//!     width: 256,
+//!     height: 128,
+//!     ..Default::default()
+//! };
+//! let world = generate_world(&params);
+//! let network = world.sea_lanes();
+//!
+//! // Каждый морской путь соединяет две акватории
+//! assert!(network.lanes.iter().all(|lane| lane.distance > 0.0));
+//! for route in &network.routes {
+//!     assert!(route.ports[0] < route.ports[1]);
+//!     assert!(!route.sea_provinces.is_empty());
+//! }
+//! ```
+
+use crate::biome::{Biome, BiomeMap};
+use crate::pathfinding::{OpenEntry, wrapped_distance};
+use crate::province::Province;
+use crate::province::graph::{BorderKind, ProvinceGraph};
+use crate::province::water::WaterType;
+use crate::strategic::StrategicPoint;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+
+/// Доля замёрзшего океана, при которой акватория непроходима для флота
+pub const ICE_BLOCKED_FRACTION: f32 = 0.5;
+
+/// Доля глубоководных пикселей, при которой акватория считается глубокой
+pub const DEEP_WATER_FRACTION: f32 = 0.5;
+
+/// Число ближайших портов, с которыми соединяется каждый порт
+pub const MAX_ROUTES_PER_PORT: usize = 5;
+
+/// Глубина акватории
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaterDepth {
+    /// Мелководье (шельф) — дешёвое плавание, доступно малым судам
+    Shallow,
+    /// Открытый океан — дороже по [`Biome::movement_cost`]
+    Deep,
+}
+
+/// Морская провинция как часть навигационной сети
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeaArea {
+    /// Идентификатор морской провинции
+    pub province_id: u32,
+    /// Глубина акватории (по доле `DeepOcean`)
+    pub depth: WaterDepth,
+    /// Доля замёрзшего океана (`FrozenOcean`), 0.0–1.0
+    pub ice_fraction: f32,
+    /// Заблокирована ли акватория льдом
+    pub ice_blocked: bool,
+    /// Средняя стоимость плавания по акватории
+    pub movement_cost: f32,
+}
+
+/// Морской путь между двумя соседними акваториями
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeaLane {
+    /// Пара морских `province_id` (меньший первым)
+    pub provinces: [u32; 2],
+    /// Расстояние между центрами акваторий в пикселях
+    pub distance: f32,
+    /// Стоимость прохода: расстояние × среднее стоимостей плавания акваторий
+    pub cost: f32,
+    /// Глубина пути: глубокий, только если обе акватории глубокие
+    pub depth: WaterDepth,
+    /// Заблокирован ли путь льдом (хотя бы одна акватория замёрзла)
+    pub ice_blocked: bool,
+}
+
+/// Маршрут между двумя портами
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortRoute {
+    /// Провинции портов (меньший `province_id` первым)
+    pub ports: [u32; 2],
+    /// Морские провинции маршрута в порядке следования от `ports[0]` к `ports[1]`
+    pub sea_provinces: Vec<u32>,
+    /// Длина маршрута в пикселях (от гавани до гавани через центры акваторий)
+    pub distance: f32,
+    /// Стоимость маршрута
+    pub cost: f32,
+}
+
+/// Сеть морских путей мира
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeaLaneNetwork {
+    /// Акватории в порядке возрастания `province_id`
+    pub seas: Vec<SeaArea>,
+    /// Морские пути в порядке возрастания пары провинций
+    pub lanes: Vec<SeaLane>,
+    /// Маршруты между портами в порядке возрастания пары портов
+    pub routes: Vec<PortRoute>,
+}
+
+/// Порт — точка выхода в море
+struct Harbor {
+    province_id: u32,
+    /// Акватории, в которые выходит порт: (слот акватории, расстояние, стоимость)
+    exits: Vec<(usize, f32, f32)>,
+}
+
+/// Строит сеть морских путей
+///
+/// # Параметры
+/// * `provinces` — провинции мира (центры и тип поверхности)
+/// * `pixel_to_id` — карта пикселей → `province_id`
+/// * `biome_map` — карта биомов (размеры карты, глубина, лёд и стоимость плавания)
+/// * `water_type` — классификация воды (учитываются только пиксели океана)
+/// * `graph` — граф смежности провинций с атрибутами границ
+/// * `strategic_points` — стратегические точки; порты и устья становятся портами сети
+///
+/// # Возвращает
+/// Сеть [`SeaLaneNetwork`] с акваториями, морскими путями и маршрутами между портами.
+#[must_use]
+pub fn build_sea_lanes(
+    provinces: &[Province],
+    pixel_to_id: &[u32],
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+    graph: &ProvinceGraph,
+    strategic_points: &[StrategicPoint],
+) -> SeaLaneNetwork {
+    let width = biome_map.width as f32;
+    let by_id: HashMap<u32, &Province> = provinces.iter().map(|p| (p.id, p)).collect();
+
+    // ШАГ 1: Акватории
+    let seas = sea_areas(provinces, pixel_to_id, biome_map, water_type);
+    let slots: HashMap<u32, usize> = seas
+        .iter()
+        .enumerate()
+        .map(|(slot, sea)| (sea.province_id, slot))
+        .collect();
+
+    // ШАГ 2: Морские пути по рёбрам SeaSea
+    let mut lanes: Vec<SeaLane> = Vec::new();
+    for edge in graph.edge_references() {
+        if edge.weight().kind != BorderKind::SeaSea {
+            continue;
+        }
+        let (a, b) = (graph[edge.source()], graph[edge.target()]);
+        let (Some(&slot_a), Some(&slot_b), Some(pa), Some(pb)) =
+            (slots.get(&a), slots.get(&b), by_id.get(&a), by_id.get(&b))
+        else {
+            continue;
+        };
+        let (sea_a, sea_b) = (&seas[slot_a], &seas[slot_b]);
+        let distance = wrapped_distance(pa.center, pb.center, width);
+        lanes.push(SeaLane {
+            provinces: [a.min(b), a.max(b)],
+            distance,
+            cost: distance * f32::midpoint(sea_a.movement_cost, sea_b.movement_cost),
+            depth: if sea_a.depth == WaterDepth::Deep && sea_b.depth == WaterDepth::Deep {
+                WaterDepth::Deep
+            } else {
+                WaterDepth::Shallow
+            },
+            ice_blocked: sea_a.ice_blocked || sea_b.ice_blocked,
+        });
+    }
+    lanes.sort_by_key(|lane| lane.provinces);
+
+    // Списки смежности по незаблокированным путям: слот → (слот соседа, путь)
+    let mut adjacency: Vec<Vec<(usize, usize)>> = vec![Vec::new(); seas.len()];
+    for (lane_idx, lane) in lanes.iter().enumerate() {
+        if let (false, Some(&a), Some(&b)) = (
+            lane.ice_blocked,
+            slots.get(&lane.provinces[0]),
+            slots.get(&lane.provinces[1]),
+        ) {
+            adjacency[a].push((b, lane_idx));
+            adjacency[b].push((a, lane_idx));
+        }
+    }
+
+    // ШАГ 3: Порты и их выходы в море
+    let harbors = harbors(graph, strategic_points, &seas, &slots, &by_id, width);
+
+    // ШАГ 4: Маршруты от каждого порта к ближайшим портам
+    let routes = port_routes(&harbors, &seas, &lanes, &adjacency);
+
+    SeaLaneNetwork {
+        seas,
+        lanes,
+        routes,
+    }
+}
+
+/// Маршруты от каждого порта к [`MAX_ROUTES_PER_PORT`] ближайшим по стоимости портам
+fn port_routes(
+    harbors: &[Harbor],
+    seas: &[SeaArea],
+    lanes: &[SeaLane],
+    adjacency: &[Vec<(usize, usize)>],
+) -> Vec<PortRoute> {
+    let mut seen: BTreeSet<[u32; 2]> = BTreeSet::new();
+    let mut routes: Vec<PortRoute> = Vec::new();
+
+    for harbor in harbors {
+        let (cost, previous) = sea_distances(harbor, adjacency, lanes, seas.len());
+
+        // Стоимость захода в каждый другой порт: лучший из его выходов в море
+        let mut candidates: Vec<(f32, &Harbor, (usize, f32))> = harbors
+            .iter()
+            .filter(|other| other.province_id != harbor.province_id)
+            .filter_map(|other| {
+                other
+                    .exits
+                    .iter()
+                    .map(|&(slot, distance, exit_cost)| {
+                        (cost[slot] + exit_cost, other, (slot, distance))
+                    })
+                    .filter(|(total, ..)| total.is_finite())
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.0.total_cmp(&b.0)
+                .then(a.1.province_id.cmp(&b.1.province_id))
+        });
+
+        for (total, other, (last_slot, last_exit)) in
+            candidates.into_iter().take(MAX_ROUTES_PER_PORT)
+        {
+            let pair = [
+                harbor.province_id.min(other.province_id),
+                harbor.province_id.max(other.province_id),
+            ];
+            if !seen.insert(pair) {
+                continue;
+            }
+
+            // Восстановление цепочки акваторий от захода в порт назад к выходу из порта
+            let mut chain = vec![last_slot];
+            let mut distance = last_exit;
+            let mut slot = last_slot;
+            while let Some((prev, lane_idx)) = previous[slot] {
+                distance += lanes[lane_idx].distance;
+                chain.push(prev);
+                slot = prev;
+            }
+            distance += harbor
+                .exits
+                .iter()
+                .find(|exit| exit.0 == slot)
+                .map_or(0.0, |exit| exit.1);
+
+            // Цепочка идёт от other к harbor; разворачиваем в порядок ports[0] → ports[1]
+            if harbor.province_id < other.province_id {
+                chain.reverse();
+            }
+            routes.push(PortRoute {
+                ports: pair,
+                sea_provinces: chain.iter().map(|&slot| seas[slot].province_id).collect(),
+                distance,
+                cost: total,
+            });
+        }
+    }
+
+    routes.sort_by_key(|route| route.ports);
+    routes
+}
+
+/// Акватории: глубина, ледовитость и стоимость плавания морских провинций
+fn sea_areas(
+    provinces: &[Province],
+    pixel_to_id: &[u32],
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+) -> Vec<SeaArea> {
+    // province_id → (пиксели океана, глубоководные, замёрзшие, сумма стоимостей)
+    let mut counts: HashMap<u32, (u32, u32, u32, f64)> = provinces
+        .iter()
+        .filter(|p| !p.is_land)
+        .map(|p| (p.id, (0, 0, 0, 0.0)))
+        .collect();
+
+    for (idx, &id) in pixel_to_id.iter().enumerate() {
+        if water_type[idx] != WaterType::Ocean {
+            continue;
+        }
+        let Some(entry) = counts.get_mut(&id) else {
+            continue;
+        };
+        let biome = biome_map.data[idx];
+        entry.0 += 1;
+        entry.1 += u32::from(biome == Biome::DeepOcean);
+        entry.2 += u32::from(biome == Biome::FrozenOcean);
+        let cost = biome.movement_cost();
+        if cost.is_finite() {
+            entry.3 += f64::from(cost);
+        }
+    }
+
+    let mut seas: Vec<SeaArea> = counts
+        .into_iter()
+        .filter(|(_, (pixels, ..))| *pixels > 0)
+        .map(|(province_id, (pixels, deep, frozen, cost_sum))| {
+            let ice_fraction = frozen as f32 / pixels as f32;
+            SeaArea {
+                province_id,
+                depth: if deep as f32 / pixels as f32 >= DEEP_WATER_FRACTION {
+                    WaterDepth::Deep
+                } else {
+                    WaterDepth::Shallow
+                },
+                ice_fraction,
+                ice_blocked: ice_fraction >= ICE_BLOCKED_FRACTION,
+                movement_cost: (cost_sum / f64::from(pixels)) as f32,
+            }
+        })
+        .collect();
+    seas.sort_by_key(|sea| sea.province_id);
+    seas
+}
+
+/// Порты сети: порты и устья с выходами в незамёрзшие соседние акватории
+fn harbors(
+    graph: &ProvinceGraph,
+    strategic_points: &[StrategicPoint],
+    seas: &[SeaArea],
+    slots: &HashMap<u32, usize>,
+    by_id: &HashMap<u32, &Province>,
+    width: f32,
+) -> Vec<Harbor> {
+    strategic_points
+        .iter()
+        .filter_map(|point| match *point {
+            StrategicPoint::Port { province_id, x, y }
+            | StrategicPoint::Estuary { province_id, x, y } => {
+                Some((province_id, (x as f32, y as f32)))
+            }
+            _ => None,
+        })
+        .filter_map(|(province_id, position)| {
+            let node = graph.node_indices().find(|&n| graph[n] == province_id)?;
+            let mut exits: Vec<(usize, f32, f32)> = graph
+                .edges(node)
+                .filter(|edge| edge.weight().kind == BorderKind::LandSea)
+                .filter_map(|edge| {
+                    let sea_id = if edge.source() == node {
+                        graph[edge.target()]
+                    } else {
+                        graph[edge.source()]
+                    };
+                    let slot = *slots.get(&sea_id)?;
+                    let sea = &seas[slot];
+                    if sea.ice_blocked {
+                        return None;
+                    }
+                    let distance = wrapped_distance(position, by_id.get(&sea_id)?.center, width);
+                    Some((slot, distance, distance * sea.movement_cost))
+                })
+                .collect();
+            exits.sort_by_key(|exit| exit.0);
+            (!exits.is_empty()).then_some(Harbor { province_id, exits })
+        })
+        .collect()
+}
+
+/// Дейкстра от порта по незаблокированным путям
+///
+/// # Возвращает
+/// `(стоимость достижения каждой акватории, предшественник: (слот, путь))`
+fn sea_distances(
+    harbor: &Harbor,
+    adjacency: &[Vec<(usize, usize)>],
+    lanes: &[SeaLane],
+    sea_count: usize,
+) -> (Vec<f32>, Vec<Option<(usize, usize)>>) {
+    let mut cost = vec![f32::INFINITY; sea_count];
+    let mut previous: Vec<Option<(usize, usize)>> = vec![None; sea_count];
+    let mut done = vec![false; sea_count];
+    let mut open = BinaryHeap::new();
+
+    for &(slot, _, exit_cost) in &harbor.exits {
+        if exit_cost < cost[slot] {
+            cost[slot] = exit_cost;
+            open.push(OpenEntry {
+                estimate: exit_cost,
+                idx: slot,
+            });
+        }
+    }
+
+    while let Some(OpenEntry { idx, .. }) = open.pop() {
+        if done[idx] {
+            continue;
+        }
+        done[idx] = true;
+        for &(next, lane_idx) in &adjacency[idx] {
+            let candidate = cost[idx] + lanes[lane_idx].cost;
+            if candidate < cost[next] {
+                cost[next] = candidate;
+                previous[next] = Some((idx, lane_idx));
+                open.push(OpenEntry {
+                    estimate: candidate,
+                    idx: next,
+                });
+            }
+        }
+    }
+
+    (cost, previous)
+}
@@ -0,0 +1,495 @@
+// src/pathfinding.rs
+//! Поиск путей по карте пикселей и графу провинций
+//!
+//! Этот модуль прокладывает маршруты юнитов с теми же стоимостями перемещения,
+//! что задаёт генератор ([`Biome::movement_cost`], атрибуты границ [`ProvinceBorder`]),
+//! чтобы игровой ИИ и предпросмотр маршрутов не изобретали собственные правила.
+//!
+//! ## Два уровня
+//!
+//! - **Пиксельный** ([`PathGrid`]) — A* по сетке карты: 8-связность, стоимость шага —
+//!   длина шага × среднее [`Biome::movement_cost`] двух пикселей, штраф за вход в реку.
+//!   Подходит для точной отрисовки маршрута
+//! - **Провинциальный** ([`find_province_path`]) — A* по графу смежности: стоимость
+//!   перехода — расстояние между центрами провинций × стоимость перемещения через
+//!   их общую границу, плюс штраф за пересечение реки. Подходит для стратегического ИИ
+//!
+//! ## Режимы перемещения ([`MovementMode`])
+//!
+//! - **`Land`** — армия: только суша (`WaterType::Land`) и сухопутные границы
+//! - **`Naval`** — флот: только вода и морские пути; побережье допускается лишь как
+//!   точка отправления или назначения (порт)
+//! - **`Amphibious`** — без ограничений по типу поверхности
+//!
+//! ## Особенности
+//!
+//! - **Бесшовность**: долгота «заворачивается» — маршрут может пересечь край карты
+//! - **Полюса** не соединены: по вертикали карта ограничена
+//! - **Непроходимость**: биомы с бесконечной стоимостью (`GlacialMountain`) и границы
+//!   без `movement_cost` не пересекаются
+//! - **Детерминированность**: при равной стоимости выбирается один и тот же путь
+//!
+//! [`ProvinceBorder`]: crate::province::graph::ProvinceBorder
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::pathfinding::{MovementCosts, MovementMode};
+//! use mapgen::{WorldGenerationParams, generate_world};
+//!
+//! let params = WorldGenerationParams {
+//!     width: 128,
+//!     height: 64,
+//!     ..Default::default()
+//! };
+//! let world = generate_world(&params);
+//! let costs = MovementCosts {
+//!     mode: MovementMode::Amphibious,
+//!     ..MovementCosts::default()
+//! };
+//!
+//! // Пиксельный маршрут через край карты по долготе
+//! let grid = world.path_grid(&costs);
+//! if let Some(path) = grid.find_path((2, 32), (125, 32)) {
+//!     assert_eq!(path.pixels.first(), Some(&(2, 32)));
+//!     assert_eq!(path.pixels.last(), Some(&(125, 32)));
+//!     assert!(path.pixels.len() < 10);
+//! }
+//!
+//! // Маршрут между двумя провинциями по графу
+//! let (from, to) = (world.provinces[0].id, world.provinces[1].id);
+//! let route = world.find_province_path(from, to, &costs).unwrap();
+//! assert_eq!(route.provinces.first(), Some(&from));
+//! assert_eq!(route.provinces.last(), Some(&to));
+//! ```
+
+use crate::biome::{Biome, BiomeMap};
+use crate::province::Province;
+use crate::province::graph::{BorderKind, ProvinceGraph};
+use crate::province::water::WaterType;
+use crate::rivers::RiverMap;
+use petgraph::algo::astar;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// 8 направлений шага по сетке: (dx, dy)
+const DIRECTIONS: [(i32, i32); 8] = [
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Режим перемещения юнита
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementMode {
+    /// Сухопутный юнит: только суша
+    #[default]
+    Land,
+    /// Флот: только вода (побережье — лишь начало или конец пути)
+    Naval,
+    /// Без ограничений по типу поверхности
+    Amphibious,
+}
+
+/// Параметры стоимости перемещения
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MovementCosts {
+    /// Режим перемещения
+    #[serde(default)]
+    pub mode: MovementMode,
+    /// Штраф за пересечение реки
+    ///
+    /// На пиксельном уровне добавляется при входе в речной пиксель с неречного,
+    /// на провинциальном — при переходе через сухопутную границу, пересекающую реку.
+    #[serde(default = "default_river_crossing_penalty")]
+    pub river_crossing_penalty: f32,
+}
+
+/// Штраф за пересечение реки по умолчанию (два шага по равнине)
+fn default_river_crossing_penalty() -> f32 {
+    2.0
+}
+
+impl Default for MovementCosts {
+    fn default() -> Self {
+        Self {
+            mode: MovementMode::default(),
+            river_crossing_penalty: default_river_crossing_penalty(),
+        }
+    }
+}
+
+impl MovementMode {
+    /// Может ли юнит находиться на поверхности данного типа
+    fn allows(self, is_land: bool) -> bool {
+        match self {
+            Self::Land => is_land,
+            Self::Naval => !is_land,
+            Self::Amphibious => true,
+        }
+    }
+}
+
+/// Пиксельный маршрут
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelPath {
+    /// Пиксели маршрута `(x, y)` от начала до конца включительно
+    pub pixels: Vec<(u32, u32)>,
+    /// Суммарная стоимость маршрута
+    pub cost: f32,
+    /// Провинции, через которые проходит маршрут, в порядке прохождения
+    pub provinces: Vec<u32>,
+}
+
+/// Маршрут по графу провинций
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProvincePath {
+    /// Провинции маршрута от начальной до конечной включительно
+    pub provinces: Vec<u32>,
+    /// Суммарная стоимость маршрута
+    pub cost: f32,
+}
+
+/// Сетка стоимостей для пиксельного поиска путей
+///
+/// Стоимости пикселей вычисляются один раз при построении, поэтому одну сетку
+/// выгодно использовать для многих запросов.
+#[derive(Debug, Clone)]
+pub struct PathGrid {
+    /// Ширина карты в пикселях
+    pub width: u32,
+    /// Высота карты в пикселях
+    pub height: u32,
+    /// Стоимость входа в пиксель (`INFINITY` — непроходим в данном режиме)
+    costs: Vec<f32>,
+    /// Является ли пиксель рекой
+    river: Vec<bool>,
+    /// Карта пикселей → `province_id`
+    pixel_to_id: Vec<u32>,
+    /// Минимальная стоимость проходимого пикселя (для эвристики A*)
+    min_cost: f32,
+    /// Параметры стоимости
+    settings: MovementCosts,
+    /// Исходная стоимость пикселей без учёта режима (для начала и конца пути)
+    raw_costs: Vec<f32>,
+}
+
+/// Элемент очереди A* и Дейкстры: меньшая оценка извлекается первой
+pub(crate) struct OpenEntry {
+    pub(crate) estimate: f32,
+    pub(crate) idx: usize,
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenEntry {}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Обратный порядок: BinaryHeap — max-heap
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| other.idx.cmp(&self.idx))
+    }
+}
+
+impl PathGrid {
+    /// Строит сетку стоимостей по слоям мира
+    ///
+    /// # Параметры
+    /// * `biome_map` — карта биомов (задаёт размеры и [`Biome::movement_cost`] пикселей)
+    /// * `river_map` — карта рек для штрафа за пересечение
+    /// * `water_type` — классификация воды (суша — `WaterType::Land`)
+    /// * `pixel_to_id` — карта пикселей → `province_id` для списка провинций маршрута
+    /// * `settings` — режим перемещения и штрафы
+    #[must_use]
+    pub fn new(
+        biome_map: &BiomeMap,
+        river_map: &RiverMap,
+        water_type: &[WaterType],
+        pixel_to_id: &[u32],
+        settings: &MovementCosts,
+    ) -> Self {
+        let raw_costs: Vec<f32> = biome_map.data.iter().map(Biome::movement_cost).collect();
+        let costs: Vec<f32> = raw_costs
+            .iter()
+            .zip(water_type)
+            .map(|(&cost, &water)| {
+                if settings.mode.allows(water == WaterType::Land) {
+                    cost
+                } else {
+                    f32::INFINITY
+                }
+            })
+            .collect();
+        let min_cost = raw_costs
+            .iter()
+            .copied()
+            .filter(|c| c.is_finite())
+            .fold(f32::INFINITY, f32::min);
+
+        Self {
+            width: biome_map.width,
+            height: biome_map.height,
+            costs,
+            river: river_map.data.iter().map(|&r| r > 0).collect(),
+            pixel_to_id: pixel_to_id.to_vec(),
+            min_cost: if min_cost.is_finite() { min_cost } else { 1.0 },
+            settings: *settings,
+            raw_costs,
+        }
+    }
+
+    /// Ищет маршрут минимальной стоимости между двумя пикселями (A*)
+    ///
+    /// # Параметры
+    /// * `from` — начальный пиксель `(x, y)`
+    /// * `to` — конечный пиксель `(x, y)`
+    ///
+    /// # Возвращает
+    /// - `Some(PixelPath)` — маршрут, его стоимость и пройденные провинции
+    /// - `None` — точка вне карты или маршрута не существует
+    ///
+    /// # Особенности
+    /// - Начальный и конечный пиксели допускаются независимо от режима
+    ///   (например, флот выходит из порта на суше), но не если они непроходимы
+    /// - Диагональный шаг стоит в √2 раз дороже прямого
+    #[must_use]
+    pub fn find_path(&self, from: (u32, u32), to: (u32, u32)) -> Option<PixelPath> {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        if from.0 >= self.width
+            || to.0 >= self.width
+            || from.1 >= self.height
+            || to.1 >= self.height
+        {
+            return None;
+        }
+        let start = from.1 as usize * width + from.0 as usize;
+        let goal = to.1 as usize * width + to.0 as usize;
+        if !self.raw_costs[start].is_finite() || !self.raw_costs[goal].is_finite() {
+            return None;
+        }
+        let cost_of = |idx: usize| {
+            if idx == start || idx == goal {
+                self.raw_costs[idx]
+            } else {
+                self.costs[idx]
+            }
+        };
+
+        let mut best = vec![f32::INFINITY; width * height];
+        let mut came_from = vec![usize::MAX; width * height];
+        let mut closed = vec![false; width * height];
+        let mut open = BinaryHeap::new();
+        best[start] = 0.0;
+        open.push(OpenEntry {
+            estimate: self.heuristic(start, goal),
+            idx: start,
+        });
+
+        // ШАГ 1: A* по 8-связной сетке
+        while let Some(OpenEntry { idx, .. }) = open.pop() {
+            if idx == goal {
+                break;
+            }
+            // Устаревшая запись очереди: пиксель уже раскрыт с меньшей стоимостью
+            if closed[idx] {
+                continue;
+            }
+            closed[idx] = true;
+            let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+            let here = cost_of(idx);
+
+            for &(dx, dy) in &DIRECTIONS {
+                let ny = y + dy;
+                if ny < 0 || ny >= height as i32 {
+                    continue;
+                }
+                let nx = (x + dx).rem_euclid(width as i32);
+                let nidx = ny as usize * width + nx as usize;
+                let there = cost_of(nidx);
+                if !there.is_finite() {
+                    continue;
+                }
+
+                let step = if dx != 0 && dy != 0 {
+                    std::f32::consts::SQRT_2
+                } else {
+                    1.0
+                };
+                let mut cost = step * f32::midpoint(here, there);
+                if self.river[nidx] && !self.river[idx] {
+                    cost += self.settings.river_crossing_penalty;
+                }
+
+                let candidate = best[idx] + cost;
+                if candidate < best[nidx] {
+                    best[nidx] = candidate;
+                    came_from[nidx] = idx;
+                    open.push(OpenEntry {
+                        estimate: candidate + self.heuristic(nidx, goal),
+                        idx: nidx,
+                    });
+                }
+            }
+        }
+
+        if !best[goal].is_finite() {
+            return None;
+        }
+
+        // ШАГ 2: Восстановление маршрута и списка провинций
+        let mut indices = vec![goal];
+        while let Some(&last) = indices.last()
+            && last != start
+        {
+            indices.push(came_from[last]);
+        }
+        indices.reverse();
+
+        let mut provinces: Vec<u32> = Vec::new();
+        for &idx in &indices {
+            let id = self.pixel_to_id[idx];
+            if id != u32::MAX && provinces.last() != Some(&id) {
+                provinces.push(id);
+            }
+        }
+
+        Some(PixelPath {
+            pixels: indices
+                .iter()
+                .map(|&idx| ((idx % width) as u32, (idx / width) as u32))
+                .collect(),
+            cost: best[goal],
+            provinces,
+        })
+    }
+
+    /// Допустимая эвристика: октильное расстояние с учётом бесшовности × минимальная стоимость
+    fn heuristic(&self, a: usize, b: usize) -> f32 {
+        let width = self.width as usize;
+        let dx = (a % width).abs_diff(b % width);
+        let dx = dx.min(width - dx) as f32;
+        let dy = (a / width).abs_diff(b / width) as f32;
+        let (long, short) = (dx.max(dy), dx.min(dy));
+        (long + (std::f32::consts::SQRT_2 - 1.0) * short) * self.min_cost
+    }
+}
+
+/// Ищет маршрут минимальной стоимости между провинциями по графу смежности (A*)
+///
+/// # Алгоритм
+/// 1. Стоимость перехода через границу = расстояние между центрами провинций
+///    (с учётом бесшовности) × `movement_cost` границы
+/// 2. Сухопутная граница, пересекающая реку, добавляет `river_crossing_penalty`
+/// 3. Границы без `movement_cost` и границы, запрещённые режимом, непроходимы
+/// 4. Эвристика — прямое расстояние между центрами × минимальная стоимость границы
+///
+/// # Параметры
+/// * `provinces` — провинции мира (центры для расстояний)
+/// * `graph` — граф смежности провинций
+/// * `map_width` — ширина карты в пикселях (для бесшовности по долготе)
+/// * `from`, `to` — `province_id` начала и конца маршрута
+/// * `settings` — режим перемещения и штрафы
+///
+/// # Возвращает
+/// - `Some(ProvincePath)` — провинции маршрута и его стоимость
+/// - `None` — провинция не найдена или маршрута не существует
+///
+/// # Режимы
+/// - `Land` — только сухопутные границы
+/// - `Naval` — морские пути; побережье — только первым или последним переходом
+/// - `Amphibious` — любые границы
+#[must_use]
+pub fn find_province_path(
+    provinces: &[Province],
+    graph: &ProvinceGraph,
+    map_width: u32,
+    from: u32,
+    to: u32,
+    settings: &MovementCosts,
+) -> Option<ProvincePath> {
+    let centers: HashMap<u32, (f32, f32)> = provinces.iter().map(|p| (p.id, p.center)).collect();
+    let is_land: HashMap<u32, bool> = provinces.iter().map(|p| (p.id, p.is_land)).collect();
+    let start = graph.node_indices().find(|&n| graph[n] == from)?;
+    let goal = graph.node_indices().find(|&n| graph[n] == to)?;
+    let goal_center = *centers.get(&to)?;
+    let width = map_width as f32;
+
+    let min_cost = graph
+        .edge_weights()
+        .filter_map(|b| b.movement_cost)
+        .fold(f32::INFINITY, f32::min);
+    let min_cost = if min_cost.is_finite() { min_cost } else { 1.0 };
+
+    let (cost, nodes) = astar(
+        graph,
+        start,
+        |n| n == goal,
+        |edge| {
+            let border = edge.weight();
+            let allowed = match (settings.mode, border.kind) {
+                (MovementMode::Amphibious, _)
+                | (MovementMode::Land, BorderKind::LandLand)
+                | (MovementMode::Naval, BorderKind::SeaSea) => true,
+                // Выход из порта или заход в порт: суша — только начало или конец пути
+                (MovementMode::Naval, BorderKind::LandSea) => [edge.source(), edge.target()]
+                    .into_iter()
+                    .filter(|&n| is_land.get(&graph[n]).copied().unwrap_or(false))
+                    .all(|n| n == start || n == goal),
+                _ => false,
+            };
+            let (Some(movement_cost), true) = (border.movement_cost, allowed) else {
+                return f32::INFINITY;
+            };
+            let (Some(&a), Some(&b)) = (
+                centers.get(&graph[edge.source()]),
+                centers.get(&graph[edge.target()]),
+            ) else {
+                return f32::INFINITY;
+            };
+
+            let mut cost = wrapped_distance(a, b, width) * movement_cost;
+            if border.crosses_river && border.kind == BorderKind::LandLand {
+                cost += settings.river_crossing_penalty;
+            }
+            cost
+        },
+        |n| {
+            centers
+                .get(&graph[n])
+                .map_or(0.0, |&c| wrapped_distance(c, goal_center, width) * min_cost)
+        },
+    )?;
+
+    cost.is_finite().then(|| ProvincePath {
+        provinces: nodes.into_iter().map(|n| graph[n]).collect(),
+        cost,
+    })
+}
+
+/// Евклидово расстояние между точками с учётом бесшовности по долготе
+pub(crate) fn wrapped_distance(a: (f32, f32), b: (f32, f32), width: f32) -> f32 {
+    let dx = (a.0 - b.0).abs();
+    let dx = dx.min(width - dx);
+    dx.hypot(a.1 - b.1)
+}
@@ -0,0 +1,734 @@
+// src/province/area.rs
+//! Целевая площадь провинций и балансировка размеров
+//!
+//! Без ограничений рост провинций останавливается только тогда, когда карта
+//! заполнена, и площади различаются на порядки. Этот модуль задаёт целевую,
+//! минимальную и максимальную площадь для каждого типа провинции
+//! ([`AreaSettings`], секция `[area]` конфигурации) и приводит провинции к ним.
+//!
+//! ## Этапы балансировки
+//!
+//! 1. **Сдерживание роста** ([`generate_provinces_balanced`]): чем больше площадь
+//!    провинции относительно цели, тем дороже её расширение — спорные пиксели
+//!    достаются меньшим соседям
+//! 2. **Разделение крупных** ([`balance_province_areas`]): провинция больше максимума
+//!    делится на `round(площадь / цель)` связных частей — ячейки Вороного внутри
+//!    провинции с несколькими итерациями релаксации Ллойда
+//! 3. **Слияние мелких**: провинция меньше минимума своего типа поглощается соседом
+//!    того же типа поверхности по стратегии секции `[merge]`
+//!    (см. [`MergeStrategy`](crate::province::merge::MergeStrategy))
+//! 4. **Статистика** ([`AreaReport`]): количество, минимум, максимум, среднее, медиана
+//!    и разброс площадей по типам провинций
+//!
+//! ## Цели по умолчанию
+//!
+//! Не заданная в конфигурации цель — средняя площадь провинции своей поверхности
+//! (пиксели суши или моря на число провинций). Минимум по умолчанию —
+//! [`MIN_AREA_FACTOR`] от цели, максимум — [`MAX_AREA_FACTOR`] от цели.
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::province::area::AreaSettings;
+//! use mapgen::{WorldGenerationParams, generate_world};
+//!
+//! let mut params = WorldGenerationParams {
+//!     width: 128,
+//!     height: 64,
+//!     ..Default::default()
+//! };
+//! params.area = AreaSettings {
+//!     balance: true,
+//!     ..AreaSettings::default()
+//! };
+//! let world = generate_world(&params);
+//! let report = world.area_report();
+//! assert!(report.continental.count > 0);
+//! ```
+//!
+//! [`generate_provinces_balanced`]: crate::province::growth::generate_provinces_balanced
+
+use crate::biome::BiomeMap;
+use crate::pathfinding::wrapped_distance;
+use crate::province::generator::{aggregate_pixel, hash_to_color, normalize_province};
+use crate::province::merge::{MergePolicy, merge_provinces_below};
+use crate::province::seeding::wrapped_dx;
+use crate::province::water::WaterType;
+use crate::province::{Province, ProvinceType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Минимальная площадь по умолчанию как доля целевой
+pub const MIN_AREA_FACTOR: f32 = 0.3;
+
+/// Максимальная площадь по умолчанию как кратное целевой
+pub const MAX_AREA_FACTOR: f32 = 2.5;
+
+/// Итерации релаксации Ллойда при разделении провинции
+const SPLIT_LLOYD_ITERATIONS: usize = 3;
+
+/// 4 ортогональных направления для построения частей провинции
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Ограничения площади для одного типа провинции (в пикселях)
+///
+/// Не заданные значения выводятся из цели: см. [`AreaLimits::resolve`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AreaLimits {
+    /// Целевая площадь (по умолчанию — средняя площадь провинции своей поверхности)
+    #[serde(default)]
+    pub target: Option<usize>,
+
+    /// Минимальная площадь: меньшие провинции сливаются с соседями
+    #[serde(default)]
+    pub min: Option<usize>,
+
+    /// Максимальная площадь: большие провинции разделяются
+    #[serde(default)]
+    pub max: Option<usize>,
+}
+
+impl AreaLimits {
+    /// Подставляет значения по умолчанию для не заданных ограничений
+    ///
+    /// # Параметры
+    /// * `auto_target` — цель, если она не задана явно
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::province::area::AreaLimits;
+    ///
+    /// let limits = AreaLimits { max: Some(3000), ..AreaLimits::default() };
+    /// let range = limits.resolve(1000);
+    /// assert_eq!((range.target, range.min, range.max), (1000, 300, 3000));
+    /// ```
+    #[must_use]
+    pub fn resolve(&self, auto_target: usize) -> AreaRange {
+        let target = self.target.unwrap_or(auto_target).max(1);
+        AreaRange {
+            target,
+            min: self
+                .min
+                .unwrap_or_else(|| (target as f32 * MIN_AREA_FACTOR).round() as usize),
+            max: self
+                .max
+                .unwrap_or_else(|| (target as f32 * MAX_AREA_FACTOR).round() as usize)
+                .max(target),
+        }
+    }
+}
+
+/// Итоговые ограничения площади (в пикселях)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AreaRange {
+    /// Целевая площадь
+    pub target: usize,
+    /// Минимальная площадь
+    pub min: usize,
+    /// Максимальная площадь
+    pub max: usize,
+}
+
+/// Настройки целевой площади и балансировки провинций
+///
+/// # Пример
+/// ```rust
+/// use mapgen::province::area::AreaSettings;
+///
+/// let settings: AreaSettings = toml::from_str(
+///     "balance = true\n[continental]\ntarget = 2000\nmax = 4000",
+/// )
+/// .unwrap();
+/// assert!(settings.balance);
+/// assert_eq!(settings.continental.target, Some(2000));
+/// assert_eq!(settings.oceanic.target, None);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AreaSettings {
+    /// Включить сдерживание роста, разделение крупных и слияние мелких провинций
+    #[serde(default)]
+    pub balance: bool,
+
+    /// Сила сдерживания роста: шаг провинции дорожает
+    /// в `1 + throttle × площадь / цель` раз (0 — без сдерживания)
+    #[serde(default = "default_throttle")]
+    pub throttle: f32,
+
+    /// Ограничения для континентальных провинций
+    #[serde(default)]
+    pub continental: AreaLimits,
+
+    /// Ограничения для островных провинций
+    #[serde(default)]
+    pub island: AreaLimits,
+
+    /// Ограничения для океанических провинций
+    #[serde(default)]
+    pub oceanic: AreaLimits,
+}
+
+fn default_throttle() -> f32 {
+    2.0
+}
+
+impl Default for AreaSettings {
+    fn default() -> Self {
+        Self {
+            balance: false,
+            throttle: default_throttle(),
+            continental: AreaLimits::default(),
+            island: AreaLimits::default(),
+            oceanic: AreaLimits::default(),
+        }
+    }
+}
+
+impl AreaSettings {
+    /// Ограничения площади для всех типов провинций
+    ///
+    /// # Параметры
+    /// * `land_target` — цель по умолчанию для провинций суши
+    ///   (континентальных и островных)
+    /// * `sea_target` — цель по умолчанию для океанических провинций
+    #[must_use]
+    pub fn targets(&self, land_target: usize, sea_target: usize) -> AreaTargets {
+        AreaTargets {
+            continental: self.continental.resolve(land_target),
+            island: self.island.resolve(land_target),
+            oceanic: self.oceanic.resolve(sea_target),
+        }
+    }
+}
+
+/// Итоговые ограничения площади по типам провинций
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AreaTargets {
+    /// Континентальные провинции
+    pub continental: AreaRange,
+    /// Островные провинции
+    pub island: AreaRange,
+    /// Океанические провинции
+    pub oceanic: AreaRange,
+}
+
+impl AreaTargets {
+    /// Ограничения для провинции заданного типа
+    #[must_use]
+    pub fn for_type(&self, province_type: &ProvinceType) -> AreaRange {
+        match province_type {
+            ProvinceType::Continental => self.continental,
+            ProvinceType::Island => self.island,
+            ProvinceType::Oceanic => self.oceanic,
+        }
+    }
+
+    /// Целевая площадь при росте (тип провинции суши ещё не известен —
+    /// используется цель континентальных провинций)
+    #[must_use]
+    pub fn growth_target(&self, is_land: bool) -> usize {
+        if is_land {
+            self.continental.target
+        } else {
+            self.oceanic.target
+        }
+    }
+}
+
+/// Статистика распределения площадей (в пикселях)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct AreaStats {
+    /// Количество провинций
+    pub count: usize,
+    /// Наименьшая площадь
+    pub min: usize,
+    /// Наибольшая площадь
+    pub max: usize,
+    /// Средняя площадь
+    pub mean: f32,
+    /// Медиана площадей
+    pub median: usize,
+    /// Стандартное отклонение площадей
+    pub std_dev: f32,
+}
+
+impl AreaStats {
+    /// Считает статистику по списку площадей
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::province::area::AreaStats;
+    ///
+    /// let stats = AreaStats::from_areas(&[100, 300, 200]);
+    /// assert_eq!((stats.count, stats.min, stats.max, stats.median), (3, 100, 300, 200));
+    /// assert_eq!(stats.mean, 200.0);
+    /// assert!((stats.coefficient_of_variation() - 0.408).abs() < 0.001);
+    /// ```
+    #[must_use]
+    pub fn from_areas(areas: &[usize]) -> Self {
+        if areas.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = areas.to_vec();
+        sorted.sort_unstable();
+        let count = sorted.len();
+        let mean = sorted.iter().sum::<usize>() as f64 / count as f64;
+        let variance = sorted
+            .iter()
+            .map(|&a| (a as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        Self {
+            count,
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean: mean as f32,
+            median: sorted[count / 2],
+            std_dev: variance.sqrt() as f32,
+        }
+    }
+
+    /// Коэффициент вариации (`std_dev / mean`): 0 — все провинции одного размера
+    #[must_use]
+    pub fn coefficient_of_variation(&self) -> f32 {
+        if self.mean > 0.0 {
+            self.std_dev / self.mean
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Распределение площадей по типам провинций
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct AreaReport {
+    /// Континентальные провинции
+    pub continental: AreaStats,
+    /// Островные провинции
+    pub island: AreaStats,
+    /// Океанические провинции
+    pub oceanic: AreaStats,
+}
+
+impl AreaReport {
+    /// Считает распределение площадей по списку провинций
+    #[must_use]
+    pub fn from_provinces(provinces: &[Province]) -> Self {
+        let areas = |province_type: ProvinceType| -> Vec<usize> {
+            provinces
+                .iter()
+                .filter(|p| p.province_type == province_type)
+                .map(|p| p.area)
+                .collect()
+        };
+        Self {
+            continental: AreaStats::from_areas(&areas(ProvinceType::Continental)),
+            island: AreaStats::from_areas(&areas(ProvinceType::Island)),
+            oceanic: AreaStats::from_areas(&areas(ProvinceType::Oceanic)),
+        }
+    }
+}
+
+/// Итог балансировки площадей
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AreaBalance {
+    /// Число разделённых провинций
+    pub split: usize,
+    /// Число провинций, появившихся при разделении (сверх исходных)
+    pub created: usize,
+    /// Число провинций, слитых с соседями
+    pub merged: usize,
+    /// Распределение площадей после балансировки
+    pub report: AreaReport,
+}
+
+/// Делит крупные и сливает мелкие провинции по ограничениям площади
+///
+/// # Алгоритм
+/// 1. Провинция с площадью больше `max` своего типа делится на
+///    `max(2, round(площадь / target))` частей: начальные центры равномерно
+///    выбираются вдоль кривой Мортона (Z-порядка), затем части выравниваются
+///    релаксацией Ллойда. Части строятся обходом в ширину только по пикселям
+///    провинции, а каждая её связная компонента получает собственные части,
+///    поэтому части связны (мелкий оторванный фрагмент становится отдельной
+///    частью и сливается с соседом на шаге 3)
+/// 2. Первая часть сохраняет `province_id` и имя, остальные получают новые
+///    идентификаторы; данные частей (площадь, центр, биомы, прибрежность, тип)
+///    пересчитываются по пикселям
+/// 3. Провинции с площадью меньше `min` своего типа (но не меньше
+///    [`MergePolicy::threshold`]) сливаются с соседями, как в
+///    [`merge_small_provinces`]; мелкие острова обрабатываются по
+///    `policy.keep_small_islands`
+/// 4. Идентификаторы уплотняются до `0..N`
+///
+/// # Параметры
+/// * `provinces` — провинции (модифицируются на месте)
+/// * `pixel_to_id` — карта пикселей → `province_id`, согласованная с `provinces`
+/// * `biome_map` — карта биомов (задаёт размеры карты)
+/// * `water_type` — классификация воды для пересчёта прибрежности
+/// * `targets` — ограничения площади по типам провинций
+/// * `policy` — политика слияния (стратегия, порог и обработка мелких островов)
+///
+/// # Возвращает
+/// Число разделённых и слитых провинций и распределение площадей после балансировки.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::biome::{Biome, BiomeMap};
+/// use mapgen::heightmap::Heightmap;
+/// use mapgen::province::area::{AreaLimits, AreaSettings, balance_province_areas};
+/// use mapgen::province::generator::{ProvinceSeed, generate_provinces_from_seeds};
+/// use mapgen::province::merge::MergePolicy;
+/// use mapgen::province::water::WaterType;
+///
+/// // Одна провинция суши на всю карту 32×8 при цели 64 пикселя
+/// let heightmap = Heightmap { width: 32, height: 8, data: vec![0.6; 256] };
+/// let biome_map = BiomeMap { width: 32, height: 8, data: vec![Biome::Grassland; 256] };
+/// let water_type = vec![WaterType::Land; 256];
+/// let seeds = [ProvinceSeed { x: 4.0, y: 4.0, weight: 1.0, is_land: true }];
+/// let (mut provinces, mut pixel_to_id) =
+///     generate_provinces_from_seeds(&heightmap, &biome_map, &water_type, &seeds);
+///
+/// let settings = AreaSettings {
+///     continental: AreaLimits { target: Some(64), ..AreaLimits::default() },
+///     ..AreaSettings::default()
+/// };
+/// let balance = balance_province_areas(
+///     &mut provinces,
+///     &mut pixel_to_id,
+///     &biome_map,
+///     &water_type,
+///     &settings.targets(64, 64),
+///     &MergePolicy::default(),
+/// );
+/// assert_eq!(balance.split, 1);
+/// assert_eq!(provinces.len(), 4);
+/// assert!(provinces.iter().all(|p| p.area >= 19 && p.area <= 160));
+/// ```
+///
+/// [`merge_small_provinces`]: crate::province::merge::merge_small_provinces
+pub fn balance_province_areas(
+    provinces: &mut Vec<Province>,
+    pixel_to_id: &mut [u32],
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+    targets: &AreaTargets,
+    policy: &MergePolicy,
+) -> AreaBalance {
+    let width = biome_map.width as usize;
+    let height = biome_map.height as usize;
+
+    // ШАГ 1: Пиксели крупных провинций
+    let oversized: HashMap<u32, usize> = provinces
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.area > targets.for_type(&p.province_type).max)
+        .map(|(slot, p)| (p.id, slot))
+        .collect();
+    let mut pixels: HashMap<u32, Vec<usize>> = HashMap::with_capacity(oversized.len());
+    for (idx, &pid) in pixel_to_id.iter().enumerate() {
+        if oversized.contains_key(&pid) {
+            pixels.entry(pid).or_default().push(idx);
+        }
+    }
+
+    // ШАГ 2: Разделение (в порядке списка — детерминированные новые id)
+    let mut next_id = provinces.iter().map(|p| p.id + 1).max().unwrap_or(0);
+    let mut split = 0;
+    let mut created = 0;
+    for slot in 0..provinces.len() {
+        let pid = provinces[slot].id;
+        let Some(province_pixels) = pixels.remove(&pid) else {
+            continue;
+        };
+        let target = targets.for_type(&provinces[slot].province_type).target;
+        let parts = ((province_pixels.len() as f32 / target as f32).round() as usize).max(2);
+        let (part_of, parts) = partition_pixels(&province_pixels, width, height, parts);
+        if parts < 2 {
+            continue;
+        }
+
+        let ids: Vec<u32> = std::iter::once(pid)
+            .chain((0..parts - 1).map(|i| next_id + i as u32))
+            .collect();
+        next_id += (parts - 1) as u32;
+        let mut pieces: Vec<Province> = ids
+            .iter()
+            .map(|&id| empty_piece(&provinces[slot], id))
+            .collect();
+
+        for (&idx, &part) in province_pixels.iter().zip(&part_of) {
+            pixel_to_id[idx] = ids[part];
+            aggregate_pixel(
+                &mut pieces[part],
+                idx % width,
+                idx / width,
+                width,
+                height,
+                biome_map,
+                water_type,
+            );
+        }
+        for piece in &mut pieces {
+            normalize_province(piece);
+        }
+
+        // Каждая часть содержит свой центр; пустые части (страховка от
+        // вырожденного разбиения) отбрасываются, id уплотняются на шаге 3
+        let mut pieces = pieces.into_iter().filter(|piece| piece.area > 0);
+        if let Some(first) = pieces.next() {
+            provinces[slot] = first;
+        }
+        let before = provinces.len();
+        provinces.extend(pieces);
+        split += 1;
+        created += provinces.len() - before;
+    }
+    if split > 0 {
+        println!("✂️  Разделено {split} крупных провинций (+{created} новых).");
+    }
+
+    // ШАГ 3: Слияние мелких провинций с уплотнением идентификаторов
+    let floor = policy.threshold(biome_map.width, biome_map.height);
+    let outcome = merge_provinces_below(
+        provinces,
+        pixel_to_id,
+        biome_map.width,
+        biome_map.height,
+        policy,
+        true,
+        &|p| targets.for_type(&p.province_type).min.max(floor),
+    );
+    if outcome.merged > 0 {
+        println!(
+            "🧹 Слито {} провинций меньше минимальной площади.",
+            outcome.merged
+        );
+    }
+
+    // ШАГ 4: Статистика
+    let report = AreaReport::from_provinces(provinces);
+    print_report(&report, targets);
+
+    AreaBalance {
+        split,
+        created,
+        merged: outcome.merged,
+        report,
+    }
+}
+
+/// Пустая часть разделяемой провинции: тип и принадлежность суше наследуются,
+/// площадь, центр и биомы накапливаются по пикселям части
+fn empty_piece(source: &Province, id: u32) -> Province {
+    Province {
+        id,
+        name: if id == source.id {
+            source.name.clone()
+        } else {
+            format!("Prov_{id}")
+        },
+        province_type: source.province_type.clone(),
+        color: hash_to_color(id),
+        is_land: source.is_land,
+        coastal: false,
+        center: (0.0, 0.0),
+        area: 0,
+        biomes: HashMap::new(),
+    }
+}
+
+/// Выводит распределение площадей по типам провинций и их ограничения
+fn print_report(report: &AreaReport, targets: &AreaTargets) {
+    for (label, stats, range) in [
+        ("континентальные", &report.continental, &targets.continental),
+        ("островные", &report.island, &targets.island),
+        ("океанические", &report.oceanic, &targets.oceanic),
+    ] {
+        if stats.count > 0 {
+            println!(
+                "📐 {label}: {} шт., площадь {}–{} (медиана {}, цель {} [{}–{}]), CV {:.2}",
+                stats.count,
+                stats.min,
+                stats.max,
+                stats.median,
+                range.target,
+                range.min,
+                range.max,
+                stats.coefficient_of_variation()
+            );
+        }
+    }
+}
+
+/// Делит пиксели провинции примерно на `parts` связных частей равной площади
+///
+/// Каждая связная компонента провинции (например, оторванный остров) получает
+/// долю частей по своей площади, но не меньше одной, поэтому части не
+/// пересекают разрывы провинции. Начальные центры — пиксели компоненты,
+/// равномерно выбранные вдоль кривой Мортона (Z-порядка): они различны и
+/// разнесены без перебора пар. Соседство пикселей вычисляется один раз, и каждая
+/// итерация Ллойда линейна по числу пикселей.
+///
+/// # Возвращает
+/// Номер части для каждого пикселя (в порядке `pixels`) и фактическое число частей.
+fn partition_pixels(
+    pixels: &[usize],
+    width: usize,
+    height: usize,
+    parts: usize,
+) -> (Vec<usize>, usize) {
+    let neighbours = local_neighbours(pixels, width, height);
+    let position = |idx: usize| ((idx % width) as f32, (idx / width) as f32);
+
+    // Начальные центры: середины равных отрезков пикселей компоненты в Z-порядке
+    let mut order: Vec<usize> = (0..pixels.len()).collect();
+    order.sort_by_key(|&i| (morton_key(pixels[i] % width, pixels[i] / width), i));
+    let (component_of, count) = label_components(&neighbours);
+    let mut components: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for i in order {
+        components[component_of[i]].push(i);
+    }
+    let mut centers = Vec::with_capacity(parts);
+    for component in &components {
+        let share = (component.len() * parts) as f64 / pixels.len() as f64;
+        let share = (share.round() as usize).clamp(1, component.len());
+        centers.extend((0..share).map(|k| component[(2 * k + 1) * component.len() / (2 * share)]));
+    }
+
+    let mut part_of = grow_parts(&neighbours, &centers);
+    for _ in 0..SPLIT_LLOYD_ITERATIONS {
+        // Центроид каждой части (смещения от центра — с учётом бесшовности)
+        let mut sums = vec![(0.0f64, 0.0f64, 0usize); centers.len()];
+        for (i, &part) in part_of.iter().enumerate() {
+            let (x, y) = position(pixels[i]);
+            let (cx, _) = position(pixels[centers[part]]);
+            let sum = &mut sums[part];
+            sum.0 += f64::from(wrapped_dx(x, cx, width as f32));
+            sum.1 += f64::from(y);
+            sum.2 += 1;
+        }
+        let centroids: Vec<(f32, f32)> = centers
+            .iter()
+            .zip(&sums)
+            .map(|(&c, &(sx, sy, n))| {
+                let (cx, cy) = position(pixels[c]);
+                if n > 0 {
+                    (cx + (sx / n as f64) as f32, (sy / n as f64) as f32)
+                } else {
+                    (cx, cy)
+                }
+            })
+            .collect();
+
+        // Новый центр — пиксель части, ближайший к её центроиду
+        let mut best = vec![(f32::INFINITY, 0); centers.len()];
+        for (i, &part) in part_of.iter().enumerate() {
+            let d = wrapped_distance(position(pixels[i]), centroids[part], width as f32);
+            if d < best[part].0 {
+                best[part] = (d, i);
+            }
+        }
+        for (center, &(d, i)) in centers.iter_mut().zip(&best) {
+            if d.is_finite() {
+                *center = i;
+            }
+        }
+        part_of = grow_parts(&neighbours, &centers);
+    }
+
+    (part_of, centers.len())
+}
+
+/// Соседи пикселей провинции (индексы в `pixels`; `usize::MAX` — сосед вне провинции)
+fn local_neighbours(
+    pixels: &[usize],
+    width: usize,
+    height: usize,
+) -> Vec<[usize; DIRECTIONS.len()]> {
+    let local: HashMap<usize, usize> = pixels.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+    pixels
+        .iter()
+        .map(|&idx| {
+            let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+            DIRECTIONS.map(|(dx, dy)| {
+                let ny = y + dy;
+                if ny < 0 || ny >= height as i32 {
+                    return usize::MAX;
+                }
+                let nx = (x + dx).rem_euclid(width as i32);
+                local
+                    .get(&(ny as usize * width + nx as usize))
+                    .copied()
+                    .unwrap_or(usize::MAX)
+            })
+        })
+        .collect()
+}
+
+/// Ключ Мортона (Z-порядка): чередование битов координат
+fn morton_key(x: usize, y: usize) -> u64 {
+    fn spread(v: usize) -> u64 {
+        let mut v = v as u64 & 0xFFFF_FFFF;
+        v = (v | v << 16) & 0x0000_FFFF_0000_FFFF;
+        v = (v | v << 8) & 0x00FF_00FF_00FF_00FF;
+        v = (v | v << 4) & 0x0F0F_0F0F_0F0F_0F0F;
+        v = (v | v << 2) & 0x3333_3333_3333_3333;
+        (v | v << 1) & 0x5555_5555_5555_5555
+    }
+    spread(x) | spread(y) << 1
+}
+
+/// Связные компоненты пикселей провинции
+///
+/// # Возвращает
+/// Номер компоненты для каждого пикселя и число компонент.
+fn label_components(neighbours: &[[usize; DIRECTIONS.len()]]) -> (Vec<usize>, usize) {
+    let mut component_of = vec![usize::MAX; neighbours.len()];
+    let mut count = 0;
+    let mut stack = Vec::new();
+    for start in 0..neighbours.len() {
+        if component_of[start] != usize::MAX {
+            continue;
+        }
+        component_of[start] = count;
+        stack.push(start);
+        while let Some(i) = stack.pop() {
+            for &j in &neighbours[i] {
+                if j != usize::MAX && component_of[j] == usize::MAX {
+                    component_of[j] = count;
+                    stack.push(j);
+                }
+            }
+        }
+        count += 1;
+    }
+    (component_of, count)
+}
+
+/// Многоисточниковый обход в ширину от центров частей по пикселям провинции
+///
+/// В каждой связной компоненте провинции должен быть хотя бы один центр —
+/// тогда каждый пиксель достаётся части, с центром которой он связан.
+fn grow_parts(neighbours: &[[usize; DIRECTIONS.len()]], centers: &[usize]) -> Vec<usize> {
+    let mut part_of = vec![usize::MAX; neighbours.len()];
+    let mut queue = VecDeque::with_capacity(neighbours.len());
+    for (part, &center) in centers.iter().enumerate() {
+        if part_of[center] == usize::MAX {
+            part_of[center] = part;
+            queue.push_back(center);
+        }
+    }
+
+    while let Some(i) = queue.pop_front() {
+        for &j in &neighbours[i] {
+            if j != usize::MAX && part_of[j] == usize::MAX {
+                part_of[j] = part_of[i];
+                queue.push_back(j);
+            }
+        }
+    }
+
+    part_of
+}
@@ -101,7 +101,7 @@ pub struct ProvinceSeed {
 /// let color = hash_to_color(42);
 /// assert_eq!(color, "#a1b2c3"); // примерное значение
 /// ```
-fn hash_to_color(id: u32) -> String {
+pub(crate) fn hash_to_color(id: u32) -> String {
     let mut hasher = DefaultHasher::new();
     id.hash(&mut hasher);
     let hash = hasher.finish();
@@ -111,6 +111,53 @@ fn hash_to_color(id: u32) -> String {
     format!("#{r:02x}{g:02x}{b:02x}")
 }
 
+/// Распределяет общее количество провинций между сушей и морем
+///
+/// Количество провинций пропорционально площади поверхности с учётом того,
+/// что морская провинция в `sea_province_scale` раз крупнее сухопутной:
+/// ```text
+/// num_land = total × land / (land + water / sea_province_scale)
+/// ```
+/// Каждому типу поверхности достаётся минимум одна провинция.
+///
+/// # Параметры
+/// * `land_pixels` — количество пикселей суши
+/// * `water_pixels` — количество водных пикселей (океаны и озёра)
+/// * `total_provinces` — общее количество провинций
+/// * `sea_province_scale` — во сколько раз морская провинция крупнее сухопутной
+///
+/// # Возвращает
+/// Кортеж `(сухопутные, морские)`.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::province::generator::split_province_counts;
+///
+/// // 30% суши, морские провинции в 2.5 раза крупнее
+/// let (num_land, num_sea) = split_province_counts(3000, 7000, 100, 2.5);
+/// assert_eq!((num_land, num_sea), (52, 48));
+/// ```
+#[must_use]
+pub fn split_province_counts(
+    land_pixels: usize,
+    water_pixels: usize,
+    total_provinces: usize,
+    sea_province_scale: f32,
+) -> (usize, usize) {
+    let land_weight = land_pixels as f32;
+    let sea_weight = water_pixels as f32 / sea_province_scale.max(f32::EPSILON);
+    let land_share = if land_weight + sea_weight > 0.0 {
+        land_weight / (land_weight + sea_weight)
+    } else {
+        0.5
+    };
+
+    let num_land = ((total_provinces as f32 * land_share).round() as usize)
+        .clamp(1, total_provinces.saturating_sub(1).max(1));
+    let num_sea = total_provinces.saturating_sub(num_land).max(1);
+    (num_land, num_sea)
+}
+
 /// Генерирует набор семян для провинций на основе географических критериев
 ///
 /// # Алгоритм выбора сухопутных семян
@@ -315,8 +362,6 @@ pub fn generate_province_seeds(
 ///     &seeds,
 /// );
 /// ```
-#[allow(clippy::too_many_lines)]
-#[allow(clippy::missing_panics_doc)]
 #[must_use]
 pub fn generate_provinces_from_seeds(
     heightmap: &Heightmap,
@@ -326,13 +371,55 @@ pub fn generate_provinces_from_seeds(
 ) -> (Vec<Province>, Vec<u32>) {
     let width = heightmap.width as usize;
     let height = heightmap.height as usize;
-    let total = width * height;
 
+    // ШАГ 1: Инициализация — размещение семян
+    let (mut provinces, mut province_id_map, starts) = init_provinces(seeds, width, height);
+    let mut queue: std::collections::VecDeque<GrowthStart> = starts.into_iter().collect();
+
+    // ШАГ 2: Flood Fill с агрегацией данных
+    while let Some((x, y, pid)) = queue.pop_front() {
+        let province = &mut provinces[pid as usize];
+        aggregate_pixel(province, x, y, width, height, biome_map, water_type);
+
+        // Добавление соседей (только того же типа поверхности)
+        for &(dx, dy) in &DIRECTIONS {
+            let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
+            let ny = (y as i32 + dy).clamp(0, (height - 1) as i32) as usize;
+            let nidx = ny * width + nx;
+
+            if province_id_map[nidx].is_none() {
+                let neighbor_is_land = water_type[nidx] == WaterType::Land;
+                if province.is_land == neighbor_is_land {
+                    province_id_map[nidx] = Some(pid);
+                    queue.push_back((nx, ny, pid));
+                }
+            }
+        }
+    }
+
+    // ШАГ 3–4: Финализация и заполнение оставшихся пикселей
+    let pixel_to_id = finalize_provinces(&mut provinces, province_id_map, biome_map, width, height);
+    (provinces, pixel_to_id)
+}
+
+/// Стартовый пиксель роста: `(x, y, province_id)`
+pub(crate) type GrowthStart = (usize, usize, u32);
+
+/// Шаг 1 роста провинций: создаёт провинцию для каждого семени
+///
+/// # Возвращает
+/// `(провинции, карта_принадлежности, стартовые пиксели)`.
+/// Пиксели семян уже отмечены в карте принадлежности.
+pub(crate) fn init_provinces(
+    seeds: &[ProvinceSeed],
+    width: usize,
+    height: usize,
+) -> (Vec<Province>, Vec<Option<u32>>, Vec<GrowthStart>) {
+    let total = width * height;
     let mut province_id_map: Vec<Option<u32>> = vec![None; total];
     let mut provinces: Vec<Province> = Vec::with_capacity(seeds.len());
-    let mut queue = std::collections::VecDeque::new();
+    let mut starts = Vec::with_capacity(seeds.len());
 
-    // ШАГ 1: Инициализация — размещение семян
     for (pid, seed) in seeds.iter().enumerate() {
         let x = seed.x as usize;
         let y = seed.y as usize;
@@ -355,72 +442,90 @@ pub fn generate_provinces_from_seeds(
                 biomes: HashMap::new(),
                 color: hash_to_color(pid as u32),
             });
-            queue.push_back((x, y, pid as u32));
+            starts.push((x, y, pid as u32));
         }
     }
 
-    // ШАГ 2: Flood Fill с агрегацией данных
-    while let Some((x, y, pid)) = queue.pop_front() {
-        let province = &mut provinces[pid as usize];
-        let idx = y * width + x;
-
-        // Агрегация данных
-        province.area += 1;
-        let biome_name = format!("{:?}", biome_map.data[idx]);
-        *province.biomes.entry(biome_name).or_insert(0.0) += 1.0;
-        province.center.0 += x as f32;
-        province.center.1 += y as f32;
-
-        // Проверка прибрежности (только для суши)
-        if province.is_land {
-            for &(dx, dy) in &DIRECTIONS {
-                let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
-                let ny = (y as i32 + dy).clamp(0, (height - 1) as i32) as usize;
-                let nidx = ny * width + nx;
-                if water_type[nidx] != WaterType::Land {
-                    province.coastal = true;
-                    break;
-                }
-            }
-        }
+    (provinces, province_id_map, starts)
+}
 
-        // Добавление соседей (только того же типа поверхности)
+/// Шаг 2 роста провинций: учитывает пиксель `(x, y)` в данных провинции
+///
+/// Накапливает площадь, сумму координат (для центра масс), счётчики биомов
+/// и прибрежность (для суши — сосед-вода по одному из 4 направлений).
+pub(crate) fn aggregate_pixel(
+    province: &mut Province,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+) {
+    let idx = y * width + x;
+
+    // Агрегация данных
+    province.area += 1;
+    let biome_name = format!("{:?}", biome_map.data[idx]);
+    *province.biomes.entry(biome_name).or_insert(0.0) += 1.0;
+    province.center.0 += x as f32;
+    province.center.1 += y as f32;
+
+    // Проверка прибрежности (только для суши)
+    if province.is_land {
         for &(dx, dy) in &DIRECTIONS {
             let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
             let ny = (y as i32 + dy).clamp(0, (height - 1) as i32) as usize;
             let nidx = ny * width + nx;
-
-            if province_id_map[nidx].is_none() {
-                let neighbor_is_land = water_type[nidx] == WaterType::Land;
-                if province.is_land == neighbor_is_land {
-                    province_id_map[nidx] = Some(pid);
-                    queue.push_back((nx, ny, pid));
-                }
+            if water_type[nidx] != WaterType::Land {
+                province.coastal = true;
+                break;
             }
         }
     }
+}
 
-    // ШАГ 3: Финализация — нормализация данных
-    for province in &mut provinces {
-        if province.area > 0 {
-            // Нормализация центра масс
-            province.center.0 /= province.area as f32;
-            province.center.1 /= province.area as f32;
-
-            // Нормализация биомов
-            for count in province.biomes.values_mut() {
-                *count /= province.area as f32;
-            }
-
-            // Определение типа провинции
-            province.province_type = if !province.is_land {
-                ProvinceType::Oceanic
-            } else if province.coastal && province.area < 500 {
-                ProvinceType::Island
-            } else {
-                ProvinceType::Continental
-            };
+/// Шаг 3 роста провинций: переводит накопленные суммы в итоговые данные
+///
+/// Центр — среднее координат пикселей, биомы — доли площади; тип провинции
+/// определяется по поверхности, прибрежности и площади.
+pub(crate) fn normalize_province(province: &mut Province) {
+    if province.area > 0 {
+        // Нормализация центра масс
+        province.center.0 /= province.area as f32;
+        province.center.1 /= province.area as f32;
+
+        // Нормализация биомов
+        for count in province.biomes.values_mut() {
+            *count /= province.area as f32;
         }
+
+        // Определение типа провинции
+        province.province_type = if !province.is_land {
+            ProvinceType::Oceanic
+        } else if province.coastal && province.area < 500 {
+            ProvinceType::Island
+        } else {
+            ProvinceType::Continental
+        };
+    }
+}
+
+/// Шаги 3–4 роста провинций: нормализация данных и заполнение непокрытых пикселей
+///
+/// # Возвращает
+/// Карту пикселей → `province_id` (все пиксели покрыты).
+#[allow(clippy::missing_panics_doc)]
+pub(crate) fn finalize_provinces(
+    provinces: &mut [Province],
+    mut province_id_map: Vec<Option<u32>>,
+    biome_map: &BiomeMap,
+    width: usize,
+    height: usize,
+) -> Vec<u32> {
+    // ШАГ 3: Финализация — нормализация данных
+    for province in provinces.iter_mut() {
+        normalize_province(province);
     }
 
     // ШАГ 4: Заполнение оставшихся пикселей
@@ -474,10 +579,8 @@ pub fn generate_provinces_from_seeds(
     }
 
     // Преобразуем карту в вектор u32
-    let pixel_to_id: Vec<u32> = province_id_map
+    province_id_map
         .into_iter()
         .map(|opt| opt.unwrap()) // Все пиксели покрыты после Шага 4
-        .collect();
-
-    (provinces, pixel_to_id)
+        .collect()
 }
@@ -8,6 +8,16 @@
 //! - Анализа связности мира (континенты, архипелаги)
 //! - Группировки провинций в регионы
 //!
+//! ## Атрибуты рёбер
+//!
+//! Каждое ребро описывает общую границу двух провинций ([`ProvinceBorder`]):
+//! - **Длина границы** — число пар соседних пикселей двух провинций
+//! - **Тип границы** ([`BorderKind`]) — суша–суша, суша–море (побережье) или море–море
+//!   (морской путь)
+//! - **Пересечение реки** — хотя бы один пиксель границы лежит на реке
+//! - **Пересечение гор** — не меньше половины границы проходит по горным биомам
+//! - **Средняя стоимость перемещения** через границу (по [`Biome::movement_cost`])
+//!
 //! ## Алгоритм построения
 //!
 //! 1. **Инициализация узлов**:
@@ -16,14 +26,14 @@
 //!
 //! 2. **Сканирование границ**:
 //!    - Проходим по каждому пикселю карты
-//!    - Для каждого пикселя проверяем 4 ортогональных соседа (без диагоналей)
+//!    - Для каждого пикселя проверяем соседей справа и снизу: так каждая пара
+//!      соседних пикселей учитывается ровно один раз (4-связность, без диагоналей)
 //!    - Бесшовная обработка по долготе (карта "заворачивается" по горизонтали)
-//!    - Вертикальные границы обрабатываются с отражением (полюса)
+//!    - Полюса границей не являются
 //!
-//! 3. **Добавление рёбер**:
-//!    - Если соседний пиксель принадлежит другой провинции → потенциальное ребро
-//!    - Используем `HashSet` для избежания дубликатов рёбер
-//!    - Рёбра не имеют веса (все границы равнозначны)
+//! 3. **Накопление атрибутов**:
+//!    - Пары пикселей разных провинций суммируются по паре провинций
+//!    - Рёбра добавляются в порядке возрастания пары `province_id`
 //!
 //! ## Особенности реализации
 //!
@@ -32,54 +42,136 @@
 //! - **Эффективность**: сложность O(width × height) с минимальными накладными расходами
 //! - **Бесшовность**: корректная обработка границ карты по долготе
 //!
+//! ## Экспорт
+//!
+//! [`adjacency_list`] превращает граф в плоский список рёбер ([`AdjacencyEdge`]) для
+//! сохранения в JSON (`adjacency.json` в CLI), а [`neighbor_ids`] — в списки соседей
+//! каждой провинции. Игре не нужно восстанавливать смежность по пикселям `provinces.png`.
+//!
 //! ## Пример использования
 //!
 //! ```rust
 //! // Построение графа
-//! let graph = build_province_graph_with_map(
-//!     &provinces,
-//!     &pixel_to_id,
-//!     width,
-//!     height,
-//! );
+//! let graph = build_province_graph_with_map(&provinces, &pixel_to_id, &biome_map, &river_map);
 //!
 //! // Поиск соседей провинции с id=42
 //! let node_idx = graph.node_indices()
 //!     .find(|&n| graph[n] == 42)
 //!     .unwrap();
 //!
-//! let neighbors: Vec<u32> = graph.neighbors(node_idx)
-//!     .map(|n| graph[n])
+//! // Сухопутные соседи и длина общей границы
+//! let land_neighbors: Vec<(u32, u32)> = graph
+//!     .edges(node_idx)
+//!     .filter(|e| e.weight().kind == BorderKind::LandLand)
+//!     .map(|e| (graph[e.target()], e.weight().length))
 //!     .collect();
 //! ```
 
+use crate::biome::{Biome, BiomeMap};
 use crate::province::Province;
+use crate::rivers::RiverMap;
 use petgraph::graph::UnGraph;
-use std::collections::{HashMap, HashSet};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Граф смежности провинций: узлы — `province_id`, рёбра — общие границы
+pub type ProvinceGraph = UnGraph<u32, ProvinceBorder>;
+
+/// Тип границы между провинциями по типу их поверхности
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BorderKind {
+    /// Сухопутная граница — передвижение армий
+    LandLand,
+    /// Побережье — высадка десанта, порты
+    LandSea,
+    /// Морской путь между акваториями — передвижение флота
+    SeaSea,
+}
+
+impl BorderKind {
+    /// Тип границы между провинциями с заданными типами поверхности
+    #[must_use]
+    pub fn between(a_is_land: bool, b_is_land: bool) -> Self {
+        match (a_is_land, b_is_land) {
+            (true, true) => Self::LandLand,
+            (false, false) => Self::SeaSea,
+            _ => Self::LandSea,
+        }
+    }
+}
 
-// 4 ортогональных направления (без диагоналей) — соответствует алгоритму генерации провинций
-const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+/// Общая граница двух провинций — атрибуты ребра графа смежности
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProvinceBorder {
+    /// Длина границы: число пар соседних пикселей двух провинций
+    pub length: u32,
+    /// Тип границы (суша–суша, суша–море, море–море)
+    pub kind: BorderKind,
+    /// Пересекает ли граница реку (хотя бы один пиксель границы — река)
+    pub crosses_river: bool,
+    /// Проходит ли граница по горам (не меньше половины пар пикселей — горные биомы)
+    pub crosses_mountains: bool,
+    /// Средняя стоимость перемещения через границу
+    ///
+    /// Стоимость пары пикселей — среднее [`Biome::movement_cost`] обоих пикселей;
+    /// непроходимые пары не учитываются. `None` — граница непроходима целиком.
+    pub movement_cost: Option<f32>,
+}
+
+/// Ребро графа смежности в плоском виде — запись `adjacency.json`
+///
+/// Атрибуты границы сериализуются на одном уровне с парой провинций:
+/// ```json
+/// {
+///   "provinces": [3, 17],
+///   "length": 42,
+///   "kind": "land_sea",
+///   "crosses_river": false,
+///   "crosses_mountains": false,
+///   "movement_cost": 1.0
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdjacencyEdge {
+    /// Пара `province_id` (меньший первым)
+    pub provinces: [u32; 2],
+    /// Атрибуты общей границы
+    #[serde(flatten)]
+    pub border: ProvinceBorder,
+}
+
+/// Накопитель атрибутов границы при сканировании карты
+#[derive(Default)]
+struct BorderAccumulator {
+    length: u32,
+    river_pixels: u32,
+    mountain_pairs: u32,
+    cost_sum: f64,
+    passable_pairs: u32,
+}
 
 /// Строит граф смежности провинций на основе карты пикселей
 ///
 /// # Алгоритм
 /// 1. Создаёт узел графа для каждой провинции из списка `provinces`
-/// 2. Сканирует каждый пиксель карты и его 4 ортогональных соседа:
-///    - Если сосед принадлежит другой провинции → добавляет ребро
-///    - Использует `HashSet` для предотвращения дублирования рёбер
+/// 2. Сканирует каждый пиксель карты и его соседей справа и снизу:
+///    - Если сосед принадлежит другой провинции → пара учитывается в границе
 /// 3. Обеспечивает бесшовность по долготе через `rem_euclid`
+/// 4. Добавляет по одному ребру на каждую пару соседних провинций с атрибутами
+///    границы ([`ProvinceBorder`])
 ///
 /// # Параметры
 /// * `provinces` — список всех провинций мира (должен содержать все `province_id` из `pixel_to_id`)
 /// * `pixel_to_id` — карта пикселей, где каждый элемент — `province_id` (размер: `width × height`)
-/// * `width` — ширина карты в пикселях
-/// * `height` — высота карты в пикселях
+/// * `biome_map` — карта биомов (задаёт размеры карты, горы и стоимость перемещения)
+/// * `river_map` — карта рек для определения пересечений рек
 ///
 /// # Возвращает
-/// Неориентированный граф `UnGraph<u32, ()>`, где:
+/// Неориентированный граф [`ProvinceGraph`], где:
 /// - Узлы содержат `province_id` (тип `u32`)
-/// - Рёбра не имеют полезной нагрузки (тип `()`)
-/// - Каждое ребро представляет общую границу между двумя провинциями
+/// - Рёбра содержат атрибуты общей границы ([`ProvinceBorder`])
 ///
 /// # Гарантии
 /// - Граф содержит узел для каждой провинции из `provinces`
@@ -88,48 +180,63 @@ const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
 /// - Бесшовная обработка по долготе (восточная граница соединяется с западной)
 ///
 /// # Особенности
-/// - Вертикальные границы (полюса) обрабатываются с отражением (`clamp`)
+/// - Полюса границей не являются (пиксели верхней и нижней строк не соседствуют)
 /// - Диагональные соседи игнорируются (только 4-связность)
 /// - Пиксели с `u32::MAX` пропускаются (защита от некорректных данных)
 ///
 /// # Пример
 /// ```rust
-/// let graph = build_province_graph_with_map(
-///     &all_provinces,
-///     &pixel_to_id,
-///     1024,
-///     512,
-/// );
+/// use mapgen::province::graph::BorderKind;
+/// use mapgen::{WorldGenerationParams, generate_world};
+///
+/// let params = WorldGenerationParams {
+///     width: 96,
+///     height: 48,
+///     ..Default::default()
+/// };
+/// let world = generate_world(&params);
 ///
 /// // Количество провинций в графе
-/// assert_eq!(graph.node_count(), all_provinces.len());
+/// assert_eq!(world.graph.node_count(), world.provinces.len());
 ///
-/// // Провинция 0 имеет хотя бы одного соседа
-/// let node_0 = graph.node_indices().find(|&n| graph[n] == 0).unwrap();
-/// assert!(graph.neighbors(node_0).next().is_some());
+/// // Длины всех границ положительны, морские пути соединяют только море
+/// for border in world.graph.edge_weights() {
+///     assert!(border.length > 0);
+/// }
+/// let sea_lanes = world
+///     .graph
+///     .edge_weights()
+///     .filter(|b| b.kind == BorderKind::SeaSea)
+///     .count();
+/// assert!(sea_lanes > 0);
 /// ```
 #[must_use]
 pub fn build_province_graph_with_map(
     provinces: &[Province],
     pixel_to_id: &[u32],
-    width: u32,
-    height: u32,
-) -> UnGraph<u32, ()> {
-    let mut graph = UnGraph::new_undirected();
+    biome_map: &BiomeMap,
+    river_map: &RiverMap,
+) -> ProvinceGraph {
+    let width = biome_map.width as usize;
+    let height = biome_map.height as usize;
+    let mut graph = ProvinceGraph::with_capacity(provinces.len(), provinces.len() * 3);
     let mut id_to_node = HashMap::new();
+    let mut id_is_land = HashMap::new();
 
     // Добавляем узлы для всех провинций
     for province in provinces {
         let node = graph.add_node(province.id);
         id_to_node.insert(province.id, node);
+        id_is_land.insert(province.id, province.is_land);
     }
 
-    let mut edges = HashSet::new();
+    // Упорядоченная пара province_id → накопленные атрибуты границы
+    let mut borders: BTreeMap<(u32, u32), BorderAccumulator> = BTreeMap::new();
 
     // Сканируем каждый пиксель для обнаружения границ между провинциями
     for y in 0..height {
         for x in 0..width {
-            let idx = (y * width + x) as usize;
+            let idx = y * width + x;
             let current_id = pixel_to_id[idx];
 
             // Защита от некорректных данных (в правильно сгенерированной карте не должно встречаться)
@@ -137,13 +244,10 @@ pub fn build_province_graph_with_map(
                 continue;
             }
 
-            // Проверяем 4 ортогональных соседа
-            for &(dx, dy) in &DIRECTIONS {
-                // Бесшовная обработка по долготе (ось X)
-                let nx = (x as i32 + dx).rem_euclid(width as i32) as u32;
-                // Отражение на полюсах (ось Y)
-                let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as u32;
-                let nidx = (ny * width + nx) as usize;
+            // Соседи справа (бесшовно по долготе) и снизу (без перехода через полюс)
+            let right = y * width + (x + 1) % width;
+            let below = (y + 1 < height).then(|| idx + width);
+            for nidx in std::iter::once(right).chain(below) {
                 let neighbor_id = pixel_to_id[nidx];
 
                 // Пропускаем недействительные или одинаковые ID
@@ -151,22 +255,119 @@ pub fn build_province_graph_with_map(
                     continue;
                 }
 
-                // Создаём упорядоченную пару для избежания дубликатов рёбер
-                let (a, b) = if current_id < neighbor_id {
+                // Упорядоченная пара для избежания дубликатов рёбер
+                let key = if current_id < neighbor_id {
                     (current_id, neighbor_id)
                 } else {
                     (neighbor_id, current_id)
                 };
+                let border = borders.entry(key).or_default();
+                border.length += 1;
 
-                // Добавляем ребро, если его ещё нет
-                if edges.insert((a, b))
-                    && let (Some(&node_a), Some(&node_b)) = (id_to_node.get(&a), id_to_node.get(&b))
+                let pair = [biome_map.data[idx], biome_map.data[nidx]];
+                border.river_pixels +=
+                    u32::from(river_map.data[idx] > 0) + u32::from(river_map.data[nidx] > 0);
+                if pair
+                    .iter()
+                    .any(|b| matches!(b, Biome::RockyMountain | Biome::GlacialMountain))
                 {
-                    graph.add_edge(node_a, node_b, ());
+                    border.mountain_pairs += 1;
+                }
+                let cost = f32::midpoint(pair[0].movement_cost(), pair[1].movement_cost());
+                if cost.is_finite() {
+                    border.cost_sum += f64::from(cost);
+                    border.passable_pairs += 1;
                 }
             }
         }
     }
 
+    // Добавляем рёбра с атрибутами границ
+    for ((a, b), border) in borders {
+        let (Some(&node_a), Some(&node_b)) = (id_to_node.get(&a), id_to_node.get(&b)) else {
+            continue;
+        };
+        graph.add_edge(
+            node_a,
+            node_b,
+            ProvinceBorder {
+                length: border.length,
+                kind: BorderKind::between(id_is_land[&a], id_is_land[&b]),
+                crosses_river: border.river_pixels > 0,
+                crosses_mountains: border.mountain_pairs * 2 >= border.length,
+                movement_cost: (border.passable_pairs > 0)
+                    .then(|| (border.cost_sum / f64::from(border.passable_pairs)) as f32),
+            },
+        );
+    }
+
     graph
 }
+
+/// Плоский список рёбер графа для экспорта
+///
+/// # Параметры
+/// * `graph` — граф смежности провинций
+///
+/// # Возвращает
+/// Рёбра с упорядоченной парой `province_id` (меньший первым), отсортированные
+/// по этой паре — вывод детерминирован и удобен для сравнения файлов.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::province::graph::{BorderKind, adjacency_list};
+/// use mapgen::{WorldGenerationParams, generate_world};
+///
+/// let params = WorldGenerationParams {
+///     width: 96,
+///     height: 48,
+///     ..Default::default()
+/// };
+/// let world = generate_world(&params);
+///
+/// let edges = adjacency_list(&world.graph);
+/// assert_eq!(edges.len(), world.graph.edge_count());
+/// assert!(edges.iter().all(|e| e.provinces[0] < e.provinces[1]));
+///
+/// // Побережья: ровно одна из провинций — суша
+/// let coastal = edges.iter().filter(|e| e.border.kind == BorderKind::LandSea).count();
+/// assert!(coastal > 0);
+/// ```
+#[must_use]
+pub fn adjacency_list(graph: &ProvinceGraph) -> Vec<AdjacencyEdge> {
+    let mut edges: Vec<AdjacencyEdge> = graph
+        .edge_references()
+        .map(|e| {
+            let (a, b) = (graph[e.source()], graph[e.target()]);
+            AdjacencyEdge {
+                provinces: [a.min(b), a.max(b)],
+                border: *e.weight(),
+            }
+        })
+        .collect();
+    edges.sort_by_key(|e| e.provinces);
+    edges
+}
+
+/// Списки соседей каждой провинции графа
+///
+/// # Параметры
+/// * `graph` — граф смежности провинций
+///
+/// # Возвращает
+/// `province_id` → отсортированные `province_id` соседей (любого типа границы).
+/// Провинции без соседей присутствуют с пустым списком.
+#[must_use]
+pub fn neighbor_ids(graph: &ProvinceGraph) -> BTreeMap<u32, Vec<u32>> {
+    let mut neighbors: BTreeMap<u32, Vec<u32>> =
+        graph.node_weights().map(|&id| (id, Vec::new())).collect();
+    for edge in adjacency_list(graph) {
+        let [a, b] = edge.provinces;
+        neighbors.entry(a).or_default().push(b);
+        neighbors.entry(b).or_default().push(a);
+    }
+    for list in neighbors.values_mut() {
+        list.sort_unstable();
+    }
+    neighbors
+}
@@ -0,0 +1,403 @@
+// src/province/growth.rs
+//! Рост провинций с учётом стоимости расширения
+//!
+//! Этот модуль реализует альтернативный режим роста провинций
+//! ([`GrowthMode::CostWeighted`]). Вместо обхода в ширину, дающего «ромбовидные»
+//! провинции, семена растут по очереди с приоритетом (многоисточниковый Дейкстра):
+//! каждый пиксель достаётся провинции, до семени которой он «дешевле» всего.
+//! Дорого пересекать горные хребты, трудный рельеф и реки — поэтому границы
+//! провинций сами ложатся на водоразделы и русла, как исторические границы.
+//!
+//! ## Стоимость шага
+//!
+//! Переход между соседними пикселями (4-связность) стоит:
+//! 1. Среднее [`Biome::movement_cost`] двух пикселей (непроходимые биомы —
+//!    [`IMPASSABLE_COST`], чтобы провинции всё равно покрывали всю карту)
+//! 2. `elevation_weight × |Δh|` — перепад высот
+//! 3. `river_penalty` — при входе в речной пиксель с нерекового
+//!
+//! ## Особенности
+//!
+//! - **Тип поверхности**: провинция растёт только по пикселям своего типа (суша/море)
+//! - **Бесшовность**: долгота «заворачивается», провинция может пересекать край карты
+//! - **Детерминированность**: при равной стоимости выигрывает пиксель с меньшим индексом
+//! - Данные провинций (площадь, центр, биомы, прибрежность) агрегируются так же,
+//!   как в [`generate_provinces_from_seeds`]
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::province::growth::{GrowthMode, GrowthSettings};
+//! use mapgen::{WorldGenerationParams, generate_world};
+//!
+//! let mut params = WorldGenerationParams {
+//!     width: 128,
+//!     height: 64,
+//!     ..Default::default()
+//! };
+//! params.growth = GrowthSettings {
+//!     mode: GrowthMode::CostWeighted,
+//!     ..GrowthSettings::default()
+//! };
+//! let world = generate_world(&params);
+//! assert!(!world.provinces.is_empty());
+//! ```
+
+use crate::biome::{Biome, BiomeMap};
+use crate::heightmap::Heightmap;
+use crate::pathfinding::OpenEntry;
+use crate::province::Province;
+use crate::province::area::AreaTargets;
+use crate::province::generator::{
+    ProvinceSeed, aggregate_pixel, finalize_provinces, generate_provinces_from_seeds,
+    init_provinces,
+};
+use crate::province::water::WaterType;
+use crate::rivers::RiverMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BinaryHeap;
+
+/// 4 ортогональных направления роста
+const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Стоимость шага через непроходимый биом (`GlacialMountain`)
+///
+/// Конечная, чтобы ледники всё же вошли в соседние провинции, но заметно выше
+/// любых проходимых биомов — граница предпочтёт пройти по леднику.
+pub const IMPASSABLE_COST: f32 = 10.0;
+
+/// Режим роста провинций от семян
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrowthMode {
+    /// Обход в ширину (`generate_provinces_from_seeds`) — равные «ромбы»
+    #[default]
+    Flood,
+    /// Очередь с приоритетом по стоимости расширения — границы по хребтам и рекам
+    CostWeighted,
+}
+
+/// Настройки роста провинций
+///
+/// # Пример
+/// ```rust
+/// use mapgen::province::growth::{GrowthMode, GrowthSettings};
+///
+/// let settings: GrowthSettings = toml::from_str("mode = \"cost_weighted\"").unwrap();
+/// assert_eq!(settings.mode, GrowthMode::CostWeighted);
+/// assert_eq!(settings.river_penalty, 20.0);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GrowthSettings {
+    /// Режим роста
+    #[serde(default)]
+    pub mode: GrowthMode,
+
+    /// Стоимость перепада высот: добавка `elevation_weight × |Δh|` за шаг
+    /// (только для режима `cost_weighted`)
+    #[serde(default = "default_elevation_weight")]
+    pub elevation_weight: f32,
+
+    /// Штраф за вход в речной пиксель (только для режима `cost_weighted`)
+    #[serde(default = "default_river_penalty")]
+    pub river_penalty: f32,
+}
+
+fn default_elevation_weight() -> f32 {
+    500.0
+}
+
+fn default_river_penalty() -> f32 {
+    20.0
+}
+
+impl Default for GrowthSettings {
+    fn default() -> Self {
+        Self {
+            mode: GrowthMode::Flood,
+            elevation_weight: default_elevation_weight(),
+            river_penalty: default_river_penalty(),
+        }
+    }
+}
+
+/// Выращивает провинции от семян в выбранном режиме
+///
+/// Для [`GrowthMode::Flood`] — то же, что [`generate_provinces_from_seeds`];
+/// для [`GrowthMode::CostWeighted`] — [`generate_provinces_cost_weighted`].
+///
+/// # Возвращает
+/// Кортеж `(провинции, карта_пикселей_в_id_провинций)`
+#[must_use]
+pub fn grow_provinces(
+    heightmap: &Heightmap,
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+    river_map: &RiverMap,
+    seeds: &[ProvinceSeed],
+    settings: &GrowthSettings,
+) -> (Vec<Province>, Vec<u32>) {
+    match settings.mode {
+        GrowthMode::Flood => generate_provinces_from_seeds(heightmap, biome_map, water_type, seeds),
+        GrowthMode::CostWeighted => generate_provinces_cost_weighted(
+            heightmap, biome_map, water_type, river_map, seeds, settings,
+        ),
+    }
+}
+
+/// Выращивает провинции очередью с приоритетом по стоимости расширения
+///
+/// # Алгоритм
+/// 1. Все семена помещаются в очередь со стоимостью 0
+/// 2. Из очереди извлекается самый дешёвый пиксель; он закрепляется за провинцией,
+///    которая добралась до него дешевле всех, и учитывается в её данных
+/// 3. Соседи того же типа поверхности получают стоимость `текущая + шаг`
+///    (см. описание модуля), если она меньше уже известной
+/// 4. Непокрытые пиксели (изолированные от семян) отдаются ближайшему центру,
+///    как в [`generate_provinces_from_seeds`]
+///
+/// # Параметры
+/// * `heightmap` — карта высот (задаёт размеры и перепады)
+/// * `biome_map` — карта биомов
+/// * `water_type` — классификация воды (суша — `WaterType::Land`)
+/// * `river_map` — карта рек для штрафа за пересечение
+/// * `seeds` — семена провинций
+/// * `settings` — веса стоимости расширения
+///
+/// # Возвращает
+/// Кортеж `(провинции, карта_пикселей_в_id_провинций)`
+///
+/// # Пример
+/// ```rust
+/// use mapgen::biome::{Biome, BiomeMap};
+/// use mapgen::heightmap::Heightmap;
+/// use mapgen::province::generator::{ProvinceSeed, generate_provinces_from_seeds};
+/// use mapgen::province::growth::{GrowthSettings, generate_provinces_cost_weighted};
+/// use mapgen::province::water::WaterType;
+/// use mapgen::rivers::RiverMap;
+///
+/// // Кольцо 16×1 с рекой в столбце 4: обход в ширину отдаёт столбец 5 левому
+/// // семени (ближе), а рост по стоимости останавливает его у реки
+/// let heightmap = Heightmap { width: 16, height: 1, data: vec![0.6; 16] };
+/// let biome_map = BiomeMap { width: 16, height: 1, data: vec![Biome::Grassland; 16] };
+/// let mut river = vec![0; 16];
+/// river[4] = 255;
+/// let river_map = RiverMap { width: 16, height: 1, data: river };
+/// let water_type = [WaterType::Land; 16];
+/// let seeds = [
+///     ProvinceSeed { x: 2.0, y: 0.0, weight: 1.0, is_land: true },
+///     ProvinceSeed { x: 10.0, y: 0.0, weight: 1.0, is_land: true },
+/// ];
+///
+/// let (_, flood) = generate_provinces_from_seeds(&heightmap, &biome_map, &water_type, &seeds);
+/// let (provinces, pixel_to_id) = generate_provinces_cost_weighted(
+///     &heightmap,
+///     &biome_map,
+///     &water_type,
+///     &river_map,
+///     &seeds,
+///     &GrowthSettings::default(),
+/// );
+/// assert_eq!(provinces.len(), 2);
+/// assert_eq!(flood[5], 0);
+/// assert_eq!(&pixel_to_id[3..7], &[0, 0, 1, 1]);
+/// ```
+#[must_use]
+pub fn generate_provinces_cost_weighted(
+    heightmap: &Heightmap,
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+    river_map: &RiverMap,
+    seeds: &[ProvinceSeed],
+    settings: &GrowthSettings,
+) -> (Vec<Province>, Vec<u32>) {
+    grow_by_cost(
+        heightmap,
+        biome_map,
+        water_type,
+        river_map,
+        seeds,
+        Some(settings),
+        None,
+    )
+}
+
+/// Выращивает провинции со сдерживанием роста крупных провинций
+///
+/// Рост идёт очередью с приоритетом, как в [`generate_provinces_cost_weighted`]
+/// (в режиме [`GrowthMode::Flood`] все шаги стоят 1 — рельеф и реки не учитываются).
+/// Стоимость шага умножается на `1 + throttle × площадь / цель`
+/// (цель — [`AreaTargets::growth_target`]): провинция, успевшая захватить больше
+/// площади, расширяется дороже, и спорные пиксели достаются меньшим соседям.
+///
+/// # Параметры
+/// * `heightmap`, `biome_map`, `water_type`, `river_map`, `seeds` — как в
+///   [`generate_provinces_cost_weighted`]
+/// * `settings` — режим роста и веса стоимости расширения
+/// * `targets` — целевые площади провинций
+/// * `throttle` — сила сдерживания (0 — без сдерживания)
+///
+/// # Возвращает
+/// Кортеж `(провинции, карта_пикселей_в_id_провинций)`
+///
+/// # Пример
+/// ```rust
+/// use mapgen::biome::{Biome, BiomeMap};
+/// use mapgen::heightmap::Heightmap;
+/// use mapgen::province::area::AreaSettings;
+/// use mapgen::province::generator::ProvinceSeed;
+/// use mapgen::province::growth::{GrowthSettings, generate_provinces_balanced};
+/// use mapgen::province::water::WaterType;
+/// use mapgen::rivers::RiverMap;
+///
+/// // Карта 30×9: слева узкий перешеек (строка 4), справа открытая равнина,
+/// // столбец 29 — море. Семя 0 — в перешейке, семя 1 — посреди равнины
+/// let water_type: Vec<WaterType> = (0..270)
+///     .map(|i| {
+///         let (x, y) = (i % 30, i / 30);
+///         if x == 29 || (x < 10 && y != 4) { WaterType::Ocean } else { WaterType::Land }
+///     })
+///     .collect();
+/// let heightmap = Heightmap { width: 30, height: 9, data: vec![0.6; 270] };
+/// let biome_map = BiomeMap { width: 30, height: 9, data: vec![Biome::Grassland; 270] };
+/// let river_map = RiverMap { width: 30, height: 9, data: vec![0; 270] };
+/// let seeds = [
+///     ProvinceSeed { x: 2.0, y: 4.0, weight: 1.0, is_land: true },
+///     ProvinceSeed { x: 20.0, y: 4.0, weight: 1.0, is_land: true },
+///     ProvinceSeed { x: 0.0, y: 0.0, weight: 1.0, is_land: false },
+/// ];
+/// // Провинция равнины растёт быстрее и сильнее сдерживается
+/// let targets = AreaSettings::default().targets(80, 100);
+/// let grow = |throttle| {
+///     generate_provinces_balanced(
+///         &heightmap,
+///         &biome_map,
+///         &water_type,
+///         &river_map,
+///         &seeds,
+///         &GrowthSettings::default(),
+///         &targets,
+///         throttle,
+///     )
+///     .0
+/// };
+///
+/// let (free, throttled) = (grow(0.0), grow(2.0));
+/// assert!(throttled[0].area > free[0].area);
+/// ```
+#[allow(clippy::too_many_arguments)] // слои карты + настройки роста и площади
+#[must_use]
+pub fn generate_provinces_balanced(
+    heightmap: &Heightmap,
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+    river_map: &RiverMap,
+    seeds: &[ProvinceSeed],
+    settings: &GrowthSettings,
+    targets: &AreaTargets,
+    throttle: f32,
+) -> (Vec<Province>, Vec<u32>) {
+    let weights = (settings.mode == GrowthMode::CostWeighted).then_some(settings);
+    grow_by_cost(
+        heightmap,
+        biome_map,
+        water_type,
+        river_map,
+        seeds,
+        weights,
+        Some((targets, throttle)),
+    )
+}
+
+/// Рост провинций очередью с приоритетом
+///
+/// * `weights` — веса рельефа и рек; `None` — все шаги стоят 1
+/// * `throttle` — цели площади и сила сдерживания крупных провинций
+fn grow_by_cost(
+    heightmap: &Heightmap,
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+    river_map: &RiverMap,
+    seeds: &[ProvinceSeed],
+    weights: Option<&GrowthSettings>,
+    throttle: Option<(&AreaTargets, f32)>,
+) -> (Vec<Province>, Vec<u32>) {
+    let width = heightmap.width as usize;
+    let height = heightmap.height as usize;
+    let total = width * height;
+
+    // ШАГ 1: Инициализация — размещение семян
+    let (mut provinces, mut province_id_map, starts) = init_provinces(seeds, width, height);
+
+    let step_cost: Vec<f32> = biome_map
+        .data
+        .iter()
+        .map(|b| Biome::movement_cost(b).min(IMPASSABLE_COST))
+        .collect();
+    let mut best = vec![f32::INFINITY; total];
+    let mut owner = vec![u32::MAX; total];
+    let mut settled = vec![false; total];
+    let mut open = BinaryHeap::new();
+
+    for (x, y, pid) in starts {
+        let idx = y * width + x;
+        best[idx] = 0.0;
+        owner[idx] = pid;
+        open.push(OpenEntry { estimate: 0.0, idx });
+    }
+
+    // ШАГ 2: Рост по возрастанию стоимости с агрегацией данных
+    while let Some(OpenEntry { estimate, idx }) = open.pop() {
+        if settled[idx] || estimate > best[idx] {
+            continue;
+        }
+        settled[idx] = true;
+
+        let pid = owner[idx];
+        province_id_map[idx] = Some(pid);
+        let (x, y) = (idx % width, idx / width);
+        let province = &mut provinces[pid as usize];
+        aggregate_pixel(province, x, y, width, height, biome_map, water_type);
+
+        for &(dx, dy) in &DIRECTIONS {
+            let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
+            let ny = (y as i32 + dy).clamp(0, (height - 1) as i32) as usize;
+            let nidx = ny * width + nx;
+
+            if settled[nidx] || province.is_land != (water_type[nidx] == WaterType::Land) {
+                continue;
+            }
+
+            let mut step = match weights {
+                Some(w) => {
+                    let mut step = f32::midpoint(step_cost[idx], step_cost[nidx])
+                        + w.elevation_weight * (heightmap.data[nidx] - heightmap.data[idx]).abs();
+                    if river_map.data[nidx] > 0 && river_map.data[idx] == 0 {
+                        step += w.river_penalty;
+                    }
+                    step
+                }
+                None => 1.0,
+            };
+            // Сдерживание: крупная провинция расширяется дороже
+            if let Some((targets, strength)) = throttle {
+                let target = targets.growth_target(province.is_land).max(1) as f32;
+                step *= 1.0 + strength * province.area as f32 / target;
+            }
+            let cost = estimate + step;
+
+            if cost < best[nidx] {
+                best[nidx] = cost;
+                owner[nidx] = pid;
+                open.push(OpenEntry {
+                    estimate: cost,
+                    idx: nidx,
+                });
+            }
+        }
+    }
+
+    // ШАГ 3–4: Финализация и заполнение оставшихся пикселей
+    let pixel_to_id = finalize_provinces(&mut provinces, province_id_map, biome_map, width, height);
+    (provinces, pixel_to_id)
+}
@@ -0,0 +1,325 @@
+// src/province/index.rs
+//! Пространственный индекс провинций
+//!
+//! Этот модуль строит по [`ProvinceMap`] индекс, отвечающий на типовые вопросы
+//! без повторного сканирования `pixel_to_id`:
+//! - **«Какая провинция в точке (x, y)?»** — O(1), для hit-тестинга в игре
+//! - **«Все пиксели провинции N»** — горизонтальные отрезки (run-length spans)
+//! - **«Граница провинции N»** — список пограничных пикселей
+//! - **«Где находится провинция N»** — ограничивающий прямоугольник
+//!
+//! ## Представление
+//!
+//! - Пиксели провинции хранятся как отрезки `[x_start, x_end)` строк карты:
+//!   для компактных провинций это на порядок меньше списка пикселей
+//! - Пограничный пиксель — пиксель, у которого хотя бы один из 4 соседей принадлежит
+//!   другой провинции (долгота бесшовна, полюса границей не считаются)
+//! - Ограничивающий прямоугольник учитывает бесшовность по долготе: провинция,
+//!   пересекающая край карты, получает прямоугольник, «перешагивающий» его
+//!
+//! ## Сложность
+//! - Построение: O(W × H), один проход по карте
+//! - Поиск провинции в точке и доступ к данным провинции: O(1)
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::province::index::ProvinceIndex;
+//! use mapgen::province::png::ProvinceMap;
+//!
+//! // Карта 4×2: провинция 7 слева, провинция 9 справа
+//! let map = ProvinceMap::from_pixel_map(4, 2, &[7, 7, 9, 9, 7, 9, 9, 9]);
+//! let index = ProvinceIndex::from_province_map(&map);
+//!
+//! // Hit-тестинг (долгота бесшовна)
+//! assert_eq!(index.province_at(1, 0), Some(7));
+//! assert_eq!(index.province_at(-1, 1), Some(9));
+//! assert_eq!(index.province_at(0, 5), None);
+//!
+//! let entry = index.get(7).unwrap();
+//! assert_eq!(entry.area, 3);
+//! assert_eq!(index.pixels(7).collect::<Vec<_>>(), vec![0, 1, 4]);
+//! ```
+
+use crate::province::png::ProvinceMap;
+use std::collections::HashMap;
+
+/// 4 ортогональных направления для поиска пограничных пикселей
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Горизонтальный отрезок пикселей одной провинции в строке `y`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Строка карты
+    pub y: u32,
+    /// Первый пиксель отрезка (включительно)
+    pub x_start: u32,
+    /// Конец отрезка (не включительно)
+    pub x_end: u32,
+}
+
+impl Span {
+    /// Длина отрезка в пикселях
+    #[must_use]
+    pub fn len(&self) -> u32 {
+        self.x_end - self.x_start
+    }
+
+    /// Пустой ли отрезок
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.x_end == self.x_start
+    }
+}
+
+/// Ограничивающий прямоугольник провинции
+///
+/// Прямоугольник может «перешагивать» правый край карты: тогда
+/// `x + width > ширина карты`, а его часть продолжается с `x = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    /// Левый столбец
+    pub x: u32,
+    /// Верхняя строка
+    pub y: u32,
+    /// Ширина в пикселях
+    pub width: u32,
+    /// Высота в пикселях
+    pub height: u32,
+}
+
+impl BoundingBox {
+    /// Попадает ли точка в прямоугольник (с учётом бесшовности по долготе)
+    ///
+    /// # Параметры
+    /// * `x`, `y` — координаты точки на карте
+    /// * `map_width` — ширина карты в пикселях
+    #[must_use]
+    pub fn contains(&self, x: u32, y: u32, map_width: u32) -> bool {
+        let dx = (x + map_width - self.x) % map_width;
+        dx < self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Данные одной провинции в индексе
+#[derive(Debug, Clone)]
+pub struct IndexedProvince {
+    /// Идентификатор провинции
+    pub id: u32,
+    /// Площадь провинции в пикселях
+    pub area: usize,
+    /// Ограничивающий прямоугольник
+    pub bounding_box: BoundingBox,
+    /// Отрезки пикселей по строкам (в порядке сканирования карты)
+    pub spans: Vec<Span>,
+    /// Пограничные пиксели (индексы `y * width + x` в порядке сканирования)
+    pub border_pixels: Vec<usize>,
+}
+
+/// Пространственный индекс провинций
+///
+/// См. документацию модуля.
+#[derive(Debug, Clone)]
+pub struct ProvinceIndex {
+    /// Ширина карты в пикселях
+    pub width: u32,
+    /// Высота карты в пикселях
+    pub height: u32,
+    /// Карта пикселей → `province_id` для поиска за O(1)
+    pixel_to_id: Vec<u32>,
+    /// Провинции в порядке первого появления на карте
+    provinces: Vec<IndexedProvince>,
+    /// `province_id` → индекс в `provinces`
+    slots: HashMap<u32, usize>,
+}
+
+impl ProvinceIndex {
+    /// Строит индекс по карте провинций за один проход
+    ///
+    /// # Параметры
+    /// * `map` — карта провинций; пиксели с `u32::MAX` не относятся ни к одной провинции
+    ///
+    /// # Возвращает
+    /// Индекс с отрезками, пограничными пикселями и ограничивающими
+    /// прямоугольниками всех провинций карты.
+    #[must_use]
+    pub fn from_province_map(map: &ProvinceMap) -> Self {
+        let width = map.width as usize;
+        let height = map.height as usize;
+        let data = &map.data;
+        let mut provinces: Vec<IndexedProvince> = Vec::new();
+        let mut slots: HashMap<u32, usize> = HashMap::new();
+        // Диапазон строк каждой провинции: (min_y, max_y)
+        let mut rows: Vec<(u32, u32)> = Vec::new();
+
+        for y in 0..height {
+            let mut x = 0;
+            while x < width {
+                let id = data[y * width + x];
+                let x_start = x;
+                while x < width && data[y * width + x] == id {
+                    x += 1;
+                }
+                if id == u32::MAX {
+                    continue;
+                }
+
+                let slot = *slots.entry(id).or_insert_with(|| {
+                    provinces.push(IndexedProvince {
+                        id,
+                        area: 0,
+                        bounding_box: BoundingBox {
+                            x: 0,
+                            y: 0,
+                            width: 0,
+                            height: 0,
+                        },
+                        spans: Vec::new(),
+                        border_pixels: Vec::new(),
+                    });
+                    rows.push((y as u32, y as u32));
+                    provinces.len() - 1
+                });
+                let province = &mut provinces[slot];
+                province.area += x - x_start;
+                province.spans.push(Span {
+                    y: y as u32,
+                    x_start: x_start as u32,
+                    x_end: x as u32,
+                });
+                rows[slot].1 = y as u32;
+
+                // Пограничные пиксели отрезка: сосед другой провинции
+                for px in x_start..x {
+                    let is_border = DIRECTIONS.iter().any(|&(dx, dy)| {
+                        let nx = (px as i32 + dx).rem_euclid(width as i32) as usize;
+                        let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as usize;
+                        data[ny * width + nx] != id
+                    });
+                    if is_border {
+                        province.border_pixels.push(y * width + px);
+                    }
+                }
+            }
+        }
+
+        for (province, &(min_y, max_y)) in provinces.iter_mut().zip(&rows) {
+            let (x, box_width) = wrapped_extent(&province.spans, map.width);
+            province.bounding_box = BoundingBox {
+                x,
+                y: min_y,
+                width: box_width,
+                height: max_y - min_y + 1,
+            };
+        }
+
+        Self {
+            width: map.width,
+            height: map.height,
+            pixel_to_id: data.clone(),
+            provinces,
+            slots,
+        }
+    }
+
+    /// Провинция в точке `(x, y)`
+    ///
+    /// Долгота бесшовна: `x` берётся по модулю ширины карты (допускаются
+    /// отрицательные значения). Для `y` вне карты возвращает `None`.
+    #[must_use]
+    pub fn province_at(&self, x: i32, y: i32) -> Option<u32> {
+        if y < 0 || y >= self.height as i32 || self.width == 0 {
+            return None;
+        }
+        let x = x.rem_euclid(self.width as i32) as usize;
+        let id = self.pixel_to_id[y as usize * self.width as usize + x];
+        (id != u32::MAX).then_some(id)
+    }
+
+    /// Данные провинции по её `province_id`
+    #[must_use]
+    pub fn get(&self, province_id: u32) -> Option<&IndexedProvince> {
+        self.slots
+            .get(&province_id)
+            .map(|&slot| &self.provinces[slot])
+    }
+
+    /// Все провинции индекса в порядке первого появления на карте
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedProvince> {
+        self.provinces.iter()
+    }
+
+    /// Число провинций на карте
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.provinces.len()
+    }
+
+    /// Нет ли ни одной провинции на карте
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.provinces.is_empty()
+    }
+
+    /// Индексы пикселей провинции (`y * width + x`) в порядке сканирования
+    ///
+    /// Для неизвестной провинции итератор пуст.
+    pub fn pixels(&self, province_id: u32) -> impl Iterator<Item = usize> + '_ {
+        let width = self.width as usize;
+        self.get(province_id)
+            .map(|province| province.spans.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .flat_map(move |span| {
+                let row = span.y as usize * width;
+                (row + span.x_start as usize)..(row + span.x_end as usize)
+            })
+    }
+
+    /// Карта пикселей → `province_id`, по которой построен индекс
+    #[must_use]
+    pub fn pixel_to_id(&self) -> &[u32] {
+        &self.pixel_to_id
+    }
+
+    /// Пиксельные координаты `(x, y)` по индексу пикселя
+    #[must_use]
+    pub fn position(&self, idx: usize) -> (u32, u32) {
+        let width = self.width as usize;
+        ((idx % width) as u32, (idx / width) as u32)
+    }
+}
+
+/// Минимальный диапазон столбцов, покрывающий отрезки, с учётом бесшовности
+///
+/// Ищет самый широкий свободный промежуток между столбцами (включая промежуток
+/// через край карты); прямоугольник начинается сразу после него.
+///
+/// # Возвращает
+/// `(левый столбец, ширина)`
+fn wrapped_extent(spans: &[Span], map_width: u32) -> (u32, u32) {
+    let mut intervals: Vec<(u32, u32)> = spans.iter().map(|s| (s.x_start, s.x_end)).collect();
+    intervals.sort_unstable();
+
+    // Объединяем перекрывающиеся интервалы столбцов
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    let (Some(&first), Some(&last)) = (merged.first(), merged.last()) else {
+        return (0, 0);
+    };
+
+    // Промежуток через край карты: от конца последнего интервала до начала первого
+    let mut gap = (map_width - last.1 + first.0, first.0);
+    for pair in merged.windows(2) {
+        let size = pair[1].0 - pair[0].1;
+        if size > gap.0 {
+            gap = (size, pair[1].0);
+        }
+    }
+
+    (gap.1 % map_width, map_width - gap.0)
+}
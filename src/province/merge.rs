@@ -2,28 +2,43 @@
 //! Слияние мелких провинций
 //!
 //! Этот модуль реализует постобработку провинций для улучшения геймплея:
-//! удаление излишне мелких провинций путём их слияния с крупными соседями.
+//! удаление излишне мелких провинций путём их слияния с соседями.
 //!
 //! ## Зачем нужно слияние?
 //!
-//! Мелкие провинции (< 50 пикселей) создают проблемы для геймплея:
+//! Мелкие провинции (меньше порога [`MergePolicy::threshold`]) создают проблемы для геймплея:
 //! - Слишком много микро-территорий усложняет управление
 //! - Армии не могут эффективно маневрировать через "игольные уши"
 //! - Экономика становится фрагментированной и несбалансированной
 //! - Карта выглядит "зашумлённой" с множеством мелких фрагментов
 //!
+//! ## Политика слияния
+//!
+//! Поведение задаётся [`MergePolicy`] (секция `[merge]` конфигурации):
+//! - **Порог площади** масштабируется с размером карты:
+//!   `max(min_area, min_area_ratio × ширина × высота)`
+//! - **Стратегия выбора соседа** ([`MergeStrategy`]): крупнейший сосед, сосед с самым
+//!   похожим биомным составом, сосед с самой длинной общей границей или
+//!   наименьший сосед (выравнивание размеров)
+//! - **Мелкие острова** — провинции суши без соседей-суши — по умолчанию остаются
+//!   отдельными провинциями; иначе поглощаются соседней морской провинцией
+//!
 //! ## Алгоритм слияния
 //!
-//! 1. **Поиск мелких провинций**:
-//!    - Итеративно находим провинции с площадью < `MIN_AREA_THRESHOLD`
-//!    - Обработка продолжается до тех пор, пока мелкие провинции существуют
+//! 1. **Соседство и длины границ**:
+//!    - Строится пространственный индекс провинций ([`ProvinceIndex`])
+//!    - По пограничным пикселям подсчитывается длина общей границы каждой пары соседей
 //!
-//! 2. **Выбор соседа для слияния**:
+//! 2. **Очередь мелких провинций** (один проход):
+//!    - Мелкие провинции обрабатываются по возрастанию площади через приоритетную очередь
+//!    - Устаревшие записи (провинция уже слита или выросла) пропускаются
+//!    - Провинция, выросшая после слияния, но оставшаяся мелкой, возвращается в очередь
+//!
+//! 3. **Выбор соседа для слияния**:
 //!    - Рассматриваем только соседей того же типа поверхности (суша→суша, море→море)
-//!    - Выбираем соседа с **максимальной площадью** для минимизации фрагментации
-//!    - Используем граф смежности для корректного определения соседства
+//!    - Соседа выбирает стратегия; при равенстве — меньший `province_id`
 //!
-//! 3. **Слияние данных**:
+//! 4. **Слияние данных**:
 //!    - **Центр масс**: взвешенное среднее по площади
 //!      ```text
 //!      x_new = (x_large * area_large + x_small * area_small) / (area_large + area_small)
@@ -34,232 +49,500 @@
 //!      ```
 //!    - **Прибрежность**: логическое ИЛИ (`coastal_new = coastal_large || coastal_small`)
 //!    - **Площадь**: сумма площадей (`area_new = area_large + area_small`)
+//!    - **Соседи**: границы мелкой провинции переходят к поглотившей
 //!
-//! 4. **Удаление мелкой провинции**:
-//!    - Провинция удаляется из списка после передачи данных
-//!    - Граф смежности не обновляется (используется только для поиска соседей)
+//! 5. **Обновление карты пикселей**:
+//!    - Пиксели слитых провинций переписываются в `pixel_to_id` на поглотившую провинцию
+//!    - По желанию идентификаторы уплотняются до `0..N` (без пропусков)
+//!    - Возвращается таблица переназначения `старый id → новый id` для привязки
+//!      внешних данных (семян, пользовательских меток)
 //!
 //! ## Особенности реализации
 //!
 //! - **Тип поверхности сохраняется**: суша никогда не сливается с морем
-//! - **Итеративный подход**: после слияния могут появиться новые мелкие провинции → повторная обработка
-//! - **Детерминированность**: выбор соседа по максимальной площади обеспечивает воспроизводимость
-//! - **Безопасность заимствования**: данные мелкой провинции копируются перед изменением вектора
-//! - **Эффективность**: сложность O(N × M), где N — число провинций, M — число соседей
+//!   (кроме поглощения мелких островов при `keep_small_islands = false`)
+//! - **Без лимита итераций**: каждая провинция сливается не более одного раза
+//! - **Детерминированность**: порядок очереди и выбор соседа не зависят от хеширования
+//! - **Эффективность**: O(W × H) на построение индекса и O((N + E) · log N) на слияние,
+//!   где N — число провинций, E — число пар соседей
 
-use crate::province::Province;
-use petgraph::graph::UnGraph;
-use std::collections::HashMap;
+use crate::province::generator::hash_to_color;
+use crate::province::index::ProvinceIndex;
+use crate::province::png::ProvinceMap;
+use crate::province::{Province, ProvinceType};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
 
-/// Минимальная допустимая площадь провинции в пикселях
+/// Минимальная допустимая площадь провинции в пикселях (нижняя граница порога)
 ///
 /// Провинции с площадью меньше этого порога считаются "слишком мелкими" для геймплея
-/// и подлежат слиянию с крупными соседями.
+/// и подлежат слиянию с соседями.
 ///
 /// # Обоснование выбора значения
 /// - `50` пикселей ≈ 7×7 квадрат на карте
 /// - Меньше этого размера провинция не может вместить значимую инфраструктуру
 /// - Опыт игр-стратегий (Crusader Kings, Europa Universalis) показывает оптимальность этого порога
 /// - Баланс между детализацией карты и удобством управления
-const MIN_AREA_THRESHOLD: usize = 50;
-const MAX_ITERATIONS: usize = 1000; // защита от бесконечного цикла
+pub(crate) const MIN_AREA_THRESHOLD: usize = 50;
 
-/// Сливает все мелкие провинции с их крупнейшими соседями
-///
-/// # Алгоритм
-/// 1. Итеративно ищет провинции с площадью < `MIN_AREA_THRESHOLD`
-/// 2. Для каждой мелкой провинции вызывает `merge_one_small_province`
-/// 3. Повторяет поиск до тех пор, пока мелкие провинции существуют
-///    (слияние может создать новые мелкие провинции из-за изменения площадей соседей)
-///
-/// # Параметры
-/// * `provinces` — mutable-ссылка на вектор провинций для модификации
-/// * `graph` — граф смежности для определения соседей провинций
+/// Доля площади карты, ниже которой провинция считается мелкой (по умолчанию)
 ///
-/// # Эффект
-/// - Модифицирует `provinces` на месте:
-///   - Удаляет мелкие провинции
-///   - Обновляет данные крупных провинций (площадь, центр, биомы)
-/// - Выводит в консоль количество слитых провинций
+/// `0.0001` ≈ 210 пикселей на карте 2048×1024; на небольших картах действует
+/// нижняя граница `MIN_AREA_THRESHOLD`.
+const DEFAULT_MIN_AREA_RATIO: f32 = 0.0001;
+
+/// Площадь, ниже которой прибрежная провинция суши считается островной
+const ISLAND_MAX_AREA: usize = 500;
+
+/// 4 ортогональных направления для подсчёта длины границ
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Стратегия выбора соседа, поглощающего мелкую провинцию
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Крупнейший сосед — минимизирует фрагментацию (поведение по умолчанию)
+    #[default]
+    LargestNeighbor,
+    /// Сосед с самым похожим биомным составом — сохраняет однородность ландшафта
+    ///
+    /// Сходство — пересечение гистограмм: `Σ min(доля_a[биом], доля_b[биом])`.
+    SimilarBiomes,
+    /// Сосед с самой длинной общей границей — самые компактные формы
+    LongestBorder,
+    /// Наименьший сосед — выравнивает размеры провинций
+    SmallestNeighbor,
+}
+
+/// Политика слияния мелких провинций
 ///
 /// # Пример
 /// ```rust
-/// let mut provinces = vec![/* ... */];
-/// let graph = build_province_graph_with_map(&provinces, &pixel_to_id, width, height);
+/// use mapgen::province::merge::{MergePolicy, MergeStrategy};
 ///
-/// merge_small_provinces(&mut provinces, &graph);
-/// // Теперь все провинции имеют площадь >= MIN_AREA_THRESHOLD
+/// let policy: MergePolicy = toml::from_str(
+///     "min_area = 80\nmin_area_ratio = 0.0002\nstrategy = \"longest_border\"",
+/// )
+/// .unwrap();
+/// assert_eq!(policy.strategy, MergeStrategy::LongestBorder);
+/// assert!(policy.keep_small_islands);
+///
+/// // Порог масштабируется с размером карты
+/// assert_eq!(policy.threshold(256, 128), 80);
+/// assert_eq!(policy.threshold(2048, 1024), 419);
 /// ```
-pub fn merge_small_provinces(provinces: &mut Vec<Province>, graph: &UnGraph<u32, ()>) {
-    let mut merged_count = 0;
-    let mut iterations = 0;
-
-    loop {
-        iterations += 1;
-        if iterations > MAX_ITERATIONS {
-            eprintln!("⚠️  Достигнут лимит итераций слияния ({MAX_ITERATIONS})");
-            break;
-        }
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MergePolicy {
+    /// Минимальная площадь провинции в пикселях (нижняя граница порога)
+    #[serde(default = "default_min_area")]
+    pub min_area: usize,
 
-        // Находим первую мелкую провинцию
-        let small_province_id = provinces
-            .iter()
-            .find(|p| p.area < MIN_AREA_THRESHOLD)
-            .map(|p| p.id);
-
-        if let Some(small_id) = small_province_id {
-            if merge_one_small_province(provinces, graph, small_id) {
-                merged_count += 1;
-            } else {
-                // Не удалось слить — пропускаем для избежания бесконечного цикла
-                break;
-            }
-        } else {
-            // Больше нет мелких провинций
-            break;
+    /// Порог площади как доля площади карты (масштабирование с размером карты)
+    #[serde(default = "default_min_area_ratio")]
+    pub min_area_ratio: f32,
+
+    /// Стратегия выбора соседа для слияния
+    #[serde(default)]
+    pub strategy: MergeStrategy,
+
+    /// Оставлять ли мелкие острова (суша без соседей-суши) отдельными провинциями
+    ///
+    /// При `false` такой остров поглощается соседней морской провинцией.
+    #[serde(default = "default_keep_small_islands")]
+    pub keep_small_islands: bool,
+}
+
+fn default_min_area() -> usize {
+    MIN_AREA_THRESHOLD
+}
+fn default_min_area_ratio() -> f32 {
+    DEFAULT_MIN_AREA_RATIO
+}
+fn default_keep_small_islands() -> bool {
+    true
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        Self {
+            min_area: MIN_AREA_THRESHOLD,
+            min_area_ratio: DEFAULT_MIN_AREA_RATIO,
+            strategy: MergeStrategy::LargestNeighbor,
+            keep_small_islands: true,
         }
     }
+}
 
-    if merged_count > 0 {
-        println!(
-            "🧹 Слито {merged_count} мелких провинций (площадь < {MIN_AREA_THRESHOLD} пикселей)."
-        );
-    } else {
-        println!("✅ Все провинции имеют достаточный размер (≥ {MIN_AREA_THRESHOLD} пикселей).");
+impl MergePolicy {
+    /// Порог площади в пикселях для карты заданного размера
+    ///
+    /// `max(min_area, min_area_ratio × width × height)`
+    #[must_use]
+    pub fn threshold(&self, width: u32, height: u32) -> usize {
+        let scaled = f64::from(self.min_area_ratio) * f64::from(width) * f64::from(height);
+        self.min_area.max(scaled.round() as usize)
     }
 }
 
-/// Сливает одну мелкую провинцию с её крупнейшим соседом того же типа поверхности
+/// Сливает все мелкие провинции с соседями по политике слияния
 ///
 /// # Алгоритм
-/// 1. Находит мелкую провинцию по `small_id` в списке `provinces`
-/// 2. Определяет её тип поверхности (`is_land`)
-/// 3. Находит всех соседей через граф смежности
-/// 4. Фильтрует соседей по типу поверхности (только те же: суша→суша, море→море)
-/// 5. Выбирает соседа с максимальной площадью
-/// 6. Копирует данные мелкой провинции (для безопасного заимствования)
-/// 7. Выполняет слияние данных в крупной провинции:
-///    - Центр масс: взвешенное среднее по площади
-///    - Биомы: взвешенное объединение долей
-///    - Прибрежность: логическое ИЛИ
-///    - Площадь: сумма площадей
-/// 8. Удаляет мелкую провинцию из списка
+/// 1. Подсчитывает длины общих границ соседей по пространственному индексу
+/// 2. Обрабатывает мелкие провинции (площадь < [`MergePolicy::threshold`]) по
+///    возрастанию площади, сливая каждую с соседом, выбранным стратегией
+/// 3. Переписывает `pixel_to_id`: пиксели слитых провинций получают `province_id`
+///    поглотившей провинции (с учётом цепочек слияний)
+/// 4. При `compact_ids` перенумеровывает оставшиеся провинции в `0..N`
+///    (в порядке списка) и обновляет их цвета и имена по умолчанию
 ///
 /// # Параметры
-/// * `provinces` — mutable-ссылка на вектор провинций
-/// * `graph` — граф смежности для поиска соседей
-/// * `small_id` — идентификатор мелкой провинции для слияния
+/// * `provinces` — mutable-ссылка на вектор провинций для модификации
+/// * `pixel_to_id` — карта пикселей → `province_id`, согласованная с `provinces`
+/// * `width`, `height` — размеры карты в пикселях
+/// * `policy` — порог, стратегия и обработка мелких островов
+/// * `compact_ids` — перенумеровать ли провинции без пропусков
 ///
 /// # Возвращает
-/// * `true` — слияние успешно выполнено
-/// * `false` — слияние невозможно (нет подходящих соседей или провинция не найдена)
+/// Таблицу переназначения `старый province_id → новый province_id` для всех
+/// провинций до слияния: слитые провинции отображаются в поглотившую,
+/// остальные — в себя (или в новый номер при `compact_ids`).
 ///
-/// # Особенности
-/// - **Сохранение типа поверхности**: суша никогда не сливается с морем
-/// - **Безопасное заимствование**: данные копируются перед изменением вектора (решает ошибку E0502)
-/// - **Взвешенное объединение**: все данные объединяются пропорционально площади
+/// # Эффект
+/// - Модифицирует `provinces` на месте:
+///   - Удаляет слитые провинции
+///   - Обновляет данные поглотивших провинций (площадь, центр, биомы, тип)
+/// - Модифицирует `pixel_to_id`: каждый пиксель ссылается на существующую провинцию
+/// - Выводит в консоль количество слитых провинций и сохранённых островов
 ///
 /// # Пример
 /// ```rust
-/// // Провинция 42 имеет площадь 30 (< 50) и соседей 15 (площадь 200) и 27 (площадь 150)
-/// let success = merge_one_small_province(&mut provinces, &graph, 42);
-/// assert!(success);
-/// // Теперь провинция 42 удалена, провинция 15 имеет увеличенную площадь и обновлённые данные
+/// let mut provinces = vec![/* ... */];
+///
+/// let policy = MergePolicy {
+///     strategy: MergeStrategy::SimilarBiomes,
+///     ..MergePolicy::default()
+/// };
+/// let remap = merge_small_provinces(&mut provinces, &mut pixel_to_id, width, height, &policy, true);
+/// // Теперь провинции, у которых был сосед того же типа, имеют площадь
+/// // >= policy.threshold(width, height), а их идентификаторы — 0..provinces.len()
+/// let new_id = remap[&old_id];
 /// ```
-fn merge_one_small_province(
+pub fn merge_small_provinces(
     provinces: &mut Vec<Province>,
-    graph: &UnGraph<u32, ()>,
-    small_id: u32,
-) -> bool {
-    // Находим мелкую провинцию и её индекс
-    let Some(small_idx) = provinces.iter().position(|p| p.id == small_id) else {
-        return false; // провинция не найдена
-    };
+    pixel_to_id: &mut [u32],
+    width: u32,
+    height: u32,
+    policy: &MergePolicy,
+    compact_ids: bool,
+) -> HashMap<u32, u32> {
+    let threshold = policy.threshold(width, height);
+    let outcome = merge_provinces_below(
+        provinces,
+        pixel_to_id,
+        width,
+        height,
+        policy,
+        compact_ids,
+        &|_| threshold,
+    );
+
+    if outcome.merged > 0 {
+        println!(
+            "🧹 Слито {} мелких провинций (площадь < {threshold} пикселей).",
+            outcome.merged
+        );
+    } else {
+        println!("✅ Все провинции имеют достаточный размер (≥ {threshold} пикселей).");
+    }
+    if outcome.kept_islands > 0 {
+        println!(
+            "🏝️  Сохранено {} мелких островов как отдельные провинции.",
+            outcome.kept_islands
+        );
+    }
 
-    // Копируем необходимые данные мелкой провинции ДО получения изменяемой ссылки
-    // Это решает ошибку заимствования E0502
-    let small_area = provinces[small_idx].area as f32;
-    let small_center = provinces[small_idx].center;
-    let small_biomes = provinces[small_idx].biomes.clone(); // HashMap копируется
-    let small_coastal = provinces[small_idx].coastal;
-    let is_land = provinces[small_idx].is_land;
+    outcome.remap
+}
+
+/// Итог слияния провинций ниже порога площади
+pub(crate) struct MergeOutcome {
+    /// Таблица переназначения `старый province_id → новый province_id`
+    pub(crate) remap: HashMap<u32, u32>,
+    /// Число слитых провинций
+    pub(crate) merged: usize,
+    /// Число мелких островов, оставленных отдельными провинциями
+    pub(crate) kept_islands: usize,
+}
 
-    // Строим маппинг для быстрого поиска
-    let prov_map: HashMap<u32, usize> = provinces
+/// Сливает провинции, площадь которых меньше порога `min_area(провинция)`
+///
+/// Общая часть [`merge_small_provinces`] и балансировки площадей: порог может
+/// зависеть от провинции (например, от её типа). Порог и стратегию выбора соседа
+/// задают `min_area` и `policy`; консольный вывод остаётся за вызывающим.
+pub(crate) fn merge_provinces_below(
+    provinces: &mut Vec<Province>,
+    pixel_to_id: &mut [u32],
+    width: u32,
+    height: u32,
+    policy: &MergePolicy,
+    compact_ids: bool,
+    min_area: &dyn Fn(&Province) -> usize,
+) -> MergeOutcome {
+    let original_ids: Vec<u32> = provinces.iter().map(|p| p.id).collect();
+    let slots: HashMap<u32, usize> = original_ids
         .iter()
         .enumerate()
-        .map(|(i, p)| (p.id, i))
+        .map(|(slot, &id)| (id, slot))
         .collect();
-    let node_map: HashMap<u32, petgraph::graph::NodeIndex> =
-        graph.node_indices().map(|idx| (graph[idx], idx)).collect();
 
-    // Находим узел мелкой провинции в графе
-    let Some(&small_node_idx) = node_map.get(&small_id) else {
-        return false; // узел не найден в графе
-    };
+    // ШАГ 1: Соседство и длины общих границ
+    let index =
+        ProvinceIndex::from_province_map(&ProvinceMap::from_pixel_map(width, height, pixel_to_id));
+    let mut neighbors = shared_borders(&index, &slots);
+
+    // ШАГ 2: Очередь мелких провинций по возрастанию площади
+    let mut alive = vec![true; provinces.len()];
+    let mut queue: BinaryHeap<Reverse<(usize, u32)>> = provinces
+        .iter()
+        .filter(|p| p.area < min_area(p))
+        .map(|p| Reverse((p.area, p.id)))
+        .collect();
+    // Слитая провинция → провинция, поглотившая её
+    let mut absorbed_into: HashMap<u32, u32> = HashMap::new();
+    let mut kept_islands = 0;
+
+    while let Some(Reverse((area, small_id))) = queue.pop() {
+        let small = slots[&small_id];
+        if !alive[small] || provinces[small].area != area {
+            continue; // устаревшая запись очереди
+        }
+
+        // ШАГ 3: Выбор соседа
+        let is_land = provinces[small].is_land;
+        let same_surface: Vec<usize> = neighbors[small]
+            .keys()
+            .copied()
+            .filter(|&n| provinces[n].is_land == is_land)
+            .collect();
+        let candidates = if !same_surface.is_empty() {
+            same_surface
+        } else if is_land && !policy.keep_small_islands {
+            // Мелкий остров без соседей-суши поглощается морем
+            neighbors[small].keys().copied().collect()
+        } else {
+            if is_land {
+                kept_islands += 1;
+            }
+            continue;
+        };
+        let Some(target) = choose_neighbor(provinces, &neighbors, small, &candidates, policy)
+        else {
+            continue;
+        };
+
+        // ШАГ 4: Слияние данных и границ
+        absorb_province(provinces, target, small);
+        let small_borders = std::mem::take(&mut neighbors[small]);
+        for (n, length) in small_borders {
+            neighbors[n].remove(&small);
+            if n != target {
+                *neighbors[n].entry(target).or_insert(0) += length;
+                *neighbors[target].entry(n).or_insert(0) += length;
+            }
+        }
+        alive[small] = false;
+        absorbed_into.insert(small_id, provinces[target].id);
+
+        if provinces[target].area < min_area(&provinces[target]) {
+            queue.push(Reverse((provinces[target].area, provinces[target].id)));
+        }
+    }
+
+    let merged_count = absorbed_into.len();
+
+    // ШАГ 5: Удаление слитых провинций и перенумерация
+    let mut slot = 0;
+    provinces.retain(|_| {
+        slot += 1;
+        alive[slot - 1]
+    });
+    let mut renumbered: HashMap<u32, u32> = HashMap::with_capacity(provinces.len());
+    for (new_id, province) in provinces.iter_mut().enumerate() {
+        let old_id = province.id;
+        if compact_ids {
+            renumber_province(province, new_id as u32);
+        }
+        renumbered.insert(old_id, province.id);
+    }
 
-    // Находим крупнейшего соседа того же типа поверхности
-    let largest_neighbor_id = graph
-        .neighbors(small_node_idx)
-        .filter_map(|n_idx| {
-            let n_id = graph[n_idx];
-            prov_map.get(&n_id).map(|&idx| &provinces[idx])
+    // Таблица переназначения: следуем по цепочке слияний до выжившей провинции
+    let remap: HashMap<u32, u32> = original_ids
+        .into_iter()
+        .map(|old_id| {
+            let mut survivor = old_id;
+            while let Some(&next) = absorbed_into.get(&survivor) {
+                survivor = next;
+            }
+            (old_id, renumbered[&survivor])
         })
-        .filter(|&n_prov| n_prov.is_land == is_land) // только тот же тип поверхности
-        .max_by_key(|&n_prov| n_prov.area)
-        .map(|p| p.id);
-
-    if let Some(large_id) = largest_neighbor_id {
-        let large_idx = prov_map[&large_id];
-
-        // Обновляем крупную провинцию (теперь безопасно — нет конфликта заимствования)
-        let large_prov = &mut provinces[large_idx];
-        let large_area = large_prov.area as f32;
-        let total_area = small_area + large_area;
-
-        // Взвешенное обновление центра масс
-        large_prov.center.0 =
-            (large_prov.center.0 * large_area + small_center.0 * small_area) / total_area;
-        large_prov.center.1 =
-            (large_prov.center.1 * large_area + small_center.1 * small_area) / total_area;
-
-        // Взвешенное объединение биомов: пересчитываем ВСЕ биомы
-        // Собираем все уникальные биомы из обеих провинций
-        let mut all_biomes: std::collections::HashMap<String, f32> = HashMap::new();
-        
-        // Добавляем биомы крупной провинции с учётом веса площади
-        for (biome, ratio) in &large_prov.biomes {
-            all_biomes.insert(biome.clone(), ratio * large_area);
+        .collect();
+
+    if merged_count > 0 || compact_ids {
+        for pid in pixel_to_id.iter_mut() {
+            if let Some(&new_id) = remap.get(pid) {
+                *pid = new_id;
+            }
         }
-        
-        // Добавляем/обновляем биомы мелкой провинции с учётом веса площади
-        for (biome, ratio) in &small_biomes {
-            let entry = all_biomes.entry(biome.clone()).or_insert(0.0);
-            *entry += ratio * small_area;
+    }
+
+    MergeOutcome {
+        remap,
+        merged: merged_count,
+        kept_islands,
+    }
+}
+
+/// Подсчитывает длины общих границ между соседними провинциями
+///
+/// Длина границы — число пар соседних (по 4 направлениям) пикселей двух провинций.
+/// Каждая пара учитывается один раз: со стороны провинции с меньшим `province_id`.
+///
+/// # Возвращает
+/// Для каждой провинции (по её позиции в списке) — соседи (позиции) и длины границ.
+fn shared_borders(
+    index: &ProvinceIndex,
+    slots: &HashMap<u32, usize>,
+) -> Vec<HashMap<usize, usize>> {
+    let width = index.width as usize;
+    let height = index.height as usize;
+    let pixel_to_id = index.pixel_to_id();
+    let mut neighbors = vec![HashMap::new(); slots.len()];
+
+    for province in index.iter() {
+        let Some(&slot) = slots.get(&province.id) else {
+            continue;
+        };
+        for &idx in &province.border_pixels {
+            let (x, y) = (idx % width, idx / width);
+            for &(dx, dy) in &DIRECTIONS {
+                let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
+                let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as usize;
+                let neighbor_id = pixel_to_id[ny * width + nx];
+                if neighbor_id <= province.id {
+                    continue;
+                }
+                if let Some(&other) = slots.get(&neighbor_id) {
+                    *neighbors[slot].entry(other).or_insert(0) += 1;
+                    *neighbors[other].entry(slot).or_insert(0) += 1;
+                }
+            }
         }
-        
-        // Нормализуем все биомы на общую площадь
-        for ratio in all_biomes.values_mut() {
-            *ratio /= total_area;
+    }
+
+    neighbors
+}
+
+/// Выбирает соседа для слияния по стратегии политики
+///
+/// При равных оценках выбирается сосед с меньшим `province_id` (детерминированность).
+fn choose_neighbor(
+    provinces: &[Province],
+    neighbors: &[HashMap<usize, usize>],
+    small: usize,
+    candidates: &[usize],
+    policy: &MergePolicy,
+) -> Option<usize> {
+    let score = |n: usize| -> f64 {
+        match policy.strategy {
+            MergeStrategy::LargestNeighbor => provinces[n].area as f64,
+            MergeStrategy::SmallestNeighbor => -(provinces[n].area as f64),
+            MergeStrategy::LongestBorder => neighbors[small][&n] as f64,
+            MergeStrategy::SimilarBiomes => {
+                f64::from(biome_similarity(&provinces[small], &provinces[n]))
+            }
         }
-        
-        // Заменяем HashMap биомов в крупной провинции
-        large_prov.biomes = all_biomes;
+    };
 
-        // Обновление прибрежности (логическое ИЛИ)
-        large_prov.coastal = large_prov.coastal || small_coastal;
+    candidates
+        .iter()
+        .map(|&n| (score(n), Reverse(provinces[n].id), n))
+        .max_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+        .map(|(_, _, n)| n)
+}
 
-        // Обновление площади
-        large_prov.area = total_area as usize;
+/// Сходство биомного состава двух провинций (пересечение гистограмм, 0.0–1.0)
+fn biome_similarity(a: &Province, b: &Province) -> f32 {
+    a.biomes
+        .iter()
+        .map(|(biome, &ratio)| b.biomes.get(biome).map_or(0.0, |&other| ratio.min(other)))
+        .sum()
+}
 
-        // Удаляем мелкую провинцию по индексу
-        // Важно: удаляем ПОСЛЕ обновления крупной провинции
-        // Индекс остаётся корректным, так как мы не изменяли вектор до этого момента
-        provinces.remove(small_idx);
+/// Переносит данные провинции `small` в провинцию `target`
+///
+/// # Слияние данных
+/// - Центр масс: взвешенное среднее по площади
+/// - Биомы: взвешенное объединение долей
+/// - Прибрежность: логическое ИЛИ (только для одного типа поверхности —
+///   морская провинция, поглотившая остров, прибрежной не становится)
+/// - Площадь: сумма площадей
+/// - Тип провинции суши пересчитывается по новой площади
+fn absorb_province(provinces: &mut [Province], target: usize, small: usize) {
+    let small_pixels = provinces[small].area;
+    let small_area = small_pixels as f32;
+    let small_center = provinces[small].center;
+    let small_coastal = provinces[small].coastal;
+    let small_is_land = provinces[small].is_land;
+    let small_biomes = std::mem::take(&mut provinces[small].biomes);
 
-        true
-    } else {
-        // Нет подходящих соседей для слияния
-        false
+    let large_prov = &mut provinces[target];
+    let large_area = large_prov.area as f32;
+    let total_area = small_area + large_area;
+
+    // Взвешенное обновление центра масс
+    large_prov.center.0 =
+        (large_prov.center.0 * large_area + small_center.0 * small_area) / total_area;
+    large_prov.center.1 =
+        (large_prov.center.1 * large_area + small_center.1 * small_area) / total_area;
+
+    // Взвешенное объединение биомов: пересчитываем ВСЕ биомы
+    // (BTreeMap — детерминированный порядок суммирования)
+    let mut all_biomes: BTreeMap<String, f32> = BTreeMap::new();
+    for (biome, ratio) in &large_prov.biomes {
+        *all_biomes.entry(biome.clone()).or_insert(0.0) += ratio * large_area;
+    }
+    for (biome, ratio) in small_biomes {
+        *all_biomes.entry(biome).or_insert(0.0) += ratio * small_area;
+    }
+    large_prov.biomes = all_biomes
+        .into_iter()
+        .map(|(biome, weight)| (biome, weight / total_area))
+        .collect();
+
+    if small_is_land == large_prov.is_land {
+        large_prov.coastal = large_prov.coastal || small_coastal;
+    }
+    large_prov.area += small_pixels;
+
+    if large_prov.is_land {
+        large_prov.province_type = if large_prov.coastal && large_prov.area < ISLAND_MAX_AREA {
+            ProvinceType::Island
+        } else {
+            ProvinceType::Continental
+        };
+    }
+}
+
+/// Присваивает провинции новый идентификатор
+///
+/// Цвет выводится из идентификатора заново, имя по умолчанию (`Prov_{id}`)
+/// обновляется; пользовательские имена сохраняются.
+fn renumber_province(province: &mut Province, new_id: u32) {
+    if province.name == format!("Prov_{}", province.id) {
+        province.name = format!("Prov_{new_id}");
     }
+    province.id = new_id;
+    province.color = hash_to_color(new_id);
 }
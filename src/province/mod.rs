@@ -12,6 +12,16 @@
 //! 4. **Граф смежности (`graph`)** — построение графа для анализа соседства и маршрутов
 //! 5. **Визуализация (`png`)** — рендеринг карты провинций в изображение
 //! 6. **Классификация воды (`water`)** — определение типа водной поверхности
+//! 7. **Пространственный индекс (`index`)** — пиксели, границы и поиск провинции
+//!    в точке без повторного сканирования карты
+//! 8. **Статистика (`stats`)** — речные, горные и береговые пиксели каждой провинции
+//!    за один проход по карте
+//! 9. **Равномерные семена (`seeding`)** — выборка Пуассона и релаксация Ллойда
+//!    (альтернатива отбору семян по весу)
+//! 10. **Рост по стоимости (`growth`)** — очередь с приоритетом вместо flood-fill:
+//!     границы провинций следуют хребтам и рекам
+//! 11. **Площадь провинций (`area`)** — целевые размеры по типам провинций,
+//!     сдерживание роста, разделение крупных и слияние мелких провинций
 //!
 //! ## Особенности провинций
 //! - Каждая провинция имеет **уникальный цвет** для визуальной идентификации
@@ -19,10 +29,15 @@
 //! - **Прибрежность** определяется автоматически при генерации
 //! - **Тип провинции** влияет на геймплейные механики (торговля, мобилизация)
 
+pub mod area;
 pub mod generator;
 pub mod graph;
+pub mod growth;
+pub mod index;
 pub mod merge;
 pub mod png;
+pub mod seeding;
+pub mod stats;
 pub mod water;
 
 use std::collections::HashMap;
@@ -50,6 +50,7 @@
 //! assert_eq!(color, "#a1b2c3");
 //! ```
 
+use crate::error::{MapgenError, image_buffer};
 use crate::province::Province;
 use image::{ImageBuffer, Rgba};
 use std::collections::HashMap;
@@ -194,24 +195,17 @@ impl ProvinceMap {
     /// * `path` — путь к файлу для сохранения (например, `"output/provinces.png"`)
     ///
     /// # Ошибки
-    /// Возвращает ошибку в случае:
-    /// - Невозможно создать буфер изображения (некорректные размеры)
-    /// - Невозможно записать файл (нет прав, недостаточно места и т.д.)
+    /// - [`MapgenError::InvalidDimensions`] — невозможно создать буфер изображения (некорректные размеры)
+    /// - [`MapgenError::ImageEncoding`] — не удалось закодировать изображение
+    /// - [`MapgenError::Io`] — невозможно записать файл (нет прав, недостаточно места и т.д.)
     ///
     /// # Пример
     /// ```rust
     /// province_map.save_as_png(&provinces, "output/provinces.png")?;
     /// ```
-    pub fn save_as_png(
-        &self,
-        provinces: &[Province],
-        path: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save_as_png(&self, provinces: &[Province], path: &str) -> Result<(), MapgenError> {
         let rgba_data = self.to_rgba_image(provinces);
-        let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
-            ImageBuffer::from_raw(self.width, self.height, rgba_data)
-                .ok_or("Failed to create image buffer")?;
-        img.save(path)?;
-        Ok(())
+        let img: ImageBuffer<Rgba<u8>, Vec<u8>> = image_buffer(self.width, self.height, rgba_data)?;
+        img.save(path).map_err(|e| MapgenError::image(path, e))
     }
 }
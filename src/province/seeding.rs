@@ -0,0 +1,400 @@
+// src/province/seeding.rs
+//! Равномерное размещение семян провинций
+//!
+//! Этот модуль реализует альтернативный режим размещения семян
+//! ([`SeedingMode::Relaxed`]): семена расставляются выборкой Пуассона (Poisson-disk)
+//! и выравниваются релаксацией Ллойда. Провинции получаются близкими по размеру
+//! и «сотовыми» по форме, а их плотность следует пригодности территории для жизни.
+//!
+//! ## Алгоритм
+//!
+//! 1. **Пригодность** ([`habitability`]): для каждого пикселя суши — по реальной
+//!    карте температуры (оптимум в умеренном поясе) и плодородности биома.
+//!    Для океана плотность равномерна
+//! 2. **Выборка Пуассона**: пиксели перебираются в случайном порядке, пиксель
+//!    принимается, если в радиусе `r / √пригодность` нет других семян — в пригодных
+//!    районах семена плотнее. Базовый радиус `r` подбирается так, чтобы семян
+//!    хватило на заданное количество провинций; лишние отбрасываются
+//! 3. **Релаксация Ллойда**: семена несколько раз переносятся в центроид своей ячейки
+//!    Вороного (центроид взвешен пригодностью). Ячейки строятся многоисточниковым
+//!    обходом в ширину по пикселям того же типа поверхности, поэтому не
+//!    «перепрыгивают» через проливы
+//!
+//! ## Особенности
+//!
+//! - **Бесшовность**: расстояния и центроиды считаются в цилиндрическом пространстве —
+//!   долгота «заворачивается», ячейка может пересекать край карты
+//! - **Тип поверхности**: семена суши и моря размещаются независимо
+//!   (суша — `WaterType::Land`, море — `WaterType::Ocean`)
+//! - **Детерминированность**: результат зависит только от сида и входных данных
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::province::seeding::{SeedingMode, SeedingSettings};
+//! use mapgen::{WorldGenerationParams, generate_world};
+//!
+//! let mut params = WorldGenerationParams {
+//!     width: 128,
+//!     height: 64,
+//!     ..Default::default()
+//! };
+//! params.seeding = SeedingSettings {
+//!     mode: SeedingMode::Relaxed,
+//!     ..SeedingSettings::default()
+//! };
+//! let world = generate_world(&params);
+//! assert!(!world.provinces.is_empty());
+//! ```
+
+use crate::biome::{Biome, BiomeMap};
+use crate::province::generator::ProvinceSeed;
+use crate::province::water::WaterType;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 4 ортогональных направления для построения ячеек Вороного
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Температура с наибольшей пригодностью (умеренный пояс; 1.0 — экватор)
+const OPTIMAL_TEMPERATURE: f32 = 0.6;
+
+/// Нижняя граница пригодности: даже пустыни и тундра получают провинции,
+/// но с радиусом до `1 / √0.2 ≈ 2.2` раза больше
+const MIN_HABITABILITY: f32 = 0.2;
+
+/// Доля площади, покрываемая случайной плотной упаковкой дисков
+///
+/// Случайное последовательное размещение с минимальным расстоянием `r` даёт
+/// около `0.7 · S / r²` точек на площади `S`.
+const PACKING_DENSITY: f64 = 0.7;
+
+/// Максимальное число попыток подбора радиуса выборки Пуассона
+const MAX_RADIUS_ATTEMPTS: usize = 8;
+
+/// Режим размещения семян провинций
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedingMode {
+    /// Отбор кандидатов по весу пригодности (`generate_province_seeds`)
+    #[default]
+    Weighted,
+    /// Выборка Пуассона с релаксацией Ллойда — равномерные провинции
+    Relaxed,
+}
+
+/// Настройки размещения семян провинций
+///
+/// # Пример
+/// ```rust
+/// use mapgen::province::seeding::{SeedingMode, SeedingSettings};
+///
+/// let settings: SeedingSettings = toml::from_str("mode = \"relaxed\"").unwrap();
+/// assert_eq!(settings.mode, SeedingMode::Relaxed);
+/// assert_eq!(settings.lloyd_iterations, 5);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SeedingSettings {
+    /// Режим размещения семян
+    #[serde(default)]
+    pub mode: SeedingMode,
+
+    /// Число итераций релаксации Ллойда (только для режима `relaxed`)
+    #[serde(default = "default_lloyd_iterations")]
+    pub lloyd_iterations: u32,
+}
+
+fn default_lloyd_iterations() -> u32 {
+    5
+}
+
+impl Default for SeedingSettings {
+    fn default() -> Self {
+        Self {
+            mode: SeedingMode::Weighted,
+            lloyd_iterations: 5,
+        }
+    }
+}
+
+/// Пригодность пикселя суши для жизни (0.2–1.0)
+///
+/// Произведение климатического фактора (по температуре, с оптимумом в умеренном
+/// поясе) и плодородности биома.
+///
+/// # Параметры
+/// * `temperature` — температура пикселя (0.0 = полюс, 1.0 = экватор)
+/// * `biome` — биом пикселя
+///
+/// # Пример
+/// ```rust
+/// use mapgen::biome::Biome;
+/// use mapgen::province::seeding::habitability;
+///
+/// assert!(habitability(0.6, Biome::Grassland) > habitability(0.1, Biome::Tundra));
+/// assert!(habitability(0.0, Biome::Ice) >= 0.2);
+/// ```
+#[must_use]
+pub fn habitability(temperature: f32, biome: Biome) -> f32 {
+    let climate = (1.0 - (temperature - OPTIMAL_TEMPERATURE).abs() * 1.5).clamp(0.0, 1.0);
+    let fertility = match biome {
+        Biome::Grassland | Biome::TemperateForest => 1.0,
+        Biome::TropicalRainforest | Biome::Savanna | Biome::Shrubland | Biome::Taiga => 0.7,
+        Biome::Swamp | Biome::Desert | Biome::Tundra => 0.4,
+        _ => 0.2,
+    };
+    (climate * fertility).max(MIN_HABITABILITY)
+}
+
+/// Генерирует семена провинций выборкой Пуассона с релаксацией Ллойда
+///
+/// # Параметры
+/// * `biome_map` — карта биомов (размеры карты и плодородность)
+/// * `water_type` — классификация воды для разделения суши и моря
+/// * `temperature` — карта температуры (0.0 = полюс, 1.0 = экватор)
+/// * `num_land` — количество сухопутных семян
+/// * `num_sea` — количество морских семян
+/// * `seed` — сид генератора
+/// * `lloyd_iterations` — число итераций релаксации
+///
+/// # Возвращает
+/// Семена суши, затем семена моря. Вес семени — пригодность его пикселя.
+/// Семян меньше запрошенного, только если пикселей данного типа не хватает.
+#[must_use]
+pub fn generate_relaxed_seeds(
+    biome_map: &BiomeMap,
+    water_type: &[WaterType],
+    temperature: &[f32],
+    num_land: usize,
+    num_sea: usize,
+    seed: u64,
+    lloyd_iterations: u32,
+) -> Vec<ProvinceSeed> {
+    let width = biome_map.width as usize;
+    let height = biome_map.height as usize;
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+
+    // Плотность: пригодность для суши, равномерная для океана, 0 — пиксель другого типа
+    let land_density: Vec<f32> = (0..width * height)
+        .map(|idx| {
+            if water_type[idx] == WaterType::Land {
+                habitability(temperature[idx], biome_map.data[idx])
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let sea_density: Vec<f32> = water_type
+        .iter()
+        .map(|&t| if t == WaterType::Ocean { 1.0 } else { 0.0 })
+        .collect();
+
+    let mut seeds = Vec::with_capacity(num_land + num_sea);
+    for (density, count, is_land) in [
+        (&land_density, num_land, true),
+        (&sea_density, num_sea, false),
+    ] {
+        let mut points = poisson_disk(width, height, density, count, &mut rng);
+        for _ in 0..lloyd_iterations {
+            lloyd_step(width, height, density, &mut points);
+        }
+        seeds.extend(points.into_iter().map(|idx| ProvinceSeed {
+            x: (idx % width) as f32,
+            y: (idx / width) as f32,
+            weight: density[idx],
+            is_land,
+        }));
+    }
+
+    seeds
+}
+
+/// Выборка Пуассона с переменным радиусом `r / √плотность`
+///
+/// Пиксели с нулевой плотностью не выбираются. Радиус подбирается так,
+/// чтобы принятых точек было не меньше `count`; лишние отбрасываются
+/// (их пустоты закрывает релаксация Ллойда).
+fn poisson_disk(
+    width: usize,
+    height: usize,
+    density: &[f32],
+    count: usize,
+    rng: &mut impl rand::Rng,
+) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..density.len()).filter(|&i| density[i] > 0.0).collect();
+    let count = count.min(order.len());
+    if count == 0 {
+        return Vec::new();
+    }
+    order.shuffle(rng);
+
+    // Площадь, взвешенная плотностью, определяет базовый радиус
+    let weighted_area: f64 = order.iter().map(|&i| f64::from(density[i])).sum();
+    let mut radius = (PACKING_DENSITY * weighted_area / count as f64).sqrt() as f32;
+
+    let mut accepted = Vec::new();
+    for _ in 0..MAX_RADIUS_ATTEMPTS {
+        accepted = dart_throwing(width, height, density, &order, radius);
+        if accepted.len() >= count {
+            break;
+        }
+        radius *= (accepted.len() as f32 / count as f32).sqrt().min(0.95);
+    }
+
+    // Если точек всё ещё не хватает — добираем первыми непринятыми пикселями
+    if accepted.len() < count {
+        let mut taken = vec![false; density.len()];
+        for &idx in &accepted {
+            taken[idx] = true;
+        }
+        let missing = count - accepted.len();
+        accepted.extend(order.iter().copied().filter(|&i| !taken[i]).take(missing));
+    }
+    accepted.truncate(count);
+    accepted
+}
+
+/// Один проход «метания дротиков» по пикселям в заданном порядке
+///
+/// Пиксель принимается, если ближайшая принятая точка дальше его радиуса
+/// `radius / √плотность` (расстояние — с учётом бесшовности по долготе).
+fn dart_throwing(
+    width: usize,
+    height: usize,
+    density: &[f32],
+    order: &[usize],
+    radius: f32,
+) -> Vec<usize> {
+    // Пространственная сетка: размер ячейки — минимальный радиус (плотность ≤ 1)
+    let cell = radius.max(1.0);
+    let grid_w = (width as f32 / cell).ceil().max(1.0) as usize;
+    let grid_h = (height as f32 / cell).ceil().max(1.0) as usize;
+    let mut grid: Vec<Vec<usize>> = vec![Vec::new(); grid_w * grid_h];
+    let mut accepted = Vec::new();
+
+    for &idx in order {
+        let (x, y) = ((idx % width) as f32, (idx / width) as f32);
+        let r = radius / density[idx].sqrt();
+        let (cx, cy) = ((x / cell) as usize, (y / cell) as usize);
+        // Запас в одну ячейку: последний столбец сетки может быть уже остальных
+        let reach = (r / cell).ceil() as i64 + 1;
+
+        let columns: Vec<usize> = if 2 * reach + 1 >= grid_w as i64 {
+            (0..grid_w).collect()
+        } else {
+            (-reach..=reach)
+                .map(|dx| (cx as i64 + dx).rem_euclid(grid_w as i64) as usize)
+                .collect()
+        };
+        let rows = cy.saturating_sub(reach as usize)..(cy + reach as usize + 1).min(grid_h);
+
+        let blocked = rows.into_iter().any(|gy| {
+            columns.iter().any(|&gx| {
+                grid[gy * grid_w + gx].iter().any(|&other| {
+                    let dx = wrapped_dx(x, (other % width) as f32, width as f32);
+                    let dy = y - (other / width) as f32;
+                    dx * dx + dy * dy < r * r
+                })
+            })
+        });
+        if !blocked {
+            grid[cy.min(grid_h - 1) * grid_w + cx.min(grid_w - 1)].push(idx);
+            accepted.push(idx);
+        }
+    }
+
+    accepted
+}
+
+/// Одна итерация релаксации Ллойда в цилиндрическом пространстве
+///
+/// 1. Ячейки Вороного — многоисточниковый обход в ширину по пикселям с плотностью > 0
+/// 2. Центроид ячейки взвешен плотностью; долгота — смещением относительно семени
+/// 3. Семя переносится в пиксель своей ячейки, ближайший к центроиду
+fn lloyd_step(width: usize, height: usize, density: &[f32], points: &mut [usize]) {
+    let owner = voronoi_cells(width, height, density, points);
+
+    // Взвешенные суммы смещений: (Σ dx·w, Σ y·w, Σ w)
+    let mut sums = vec![(0.0f64, 0.0f64, 0.0f64); points.len()];
+    for (idx, &cell) in owner.iter().enumerate() {
+        if cell == usize::MAX {
+            continue;
+        }
+        let weight = f64::from(density[idx]);
+        let seed_x = (points[cell] % width) as f32;
+        let dx = wrapped_dx((idx % width) as f32, seed_x, width as f32);
+        let sum = &mut sums[cell];
+        sum.0 += f64::from(dx) * weight;
+        sum.1 += (idx / width) as f64 * weight;
+        sum.2 += weight;
+    }
+    let centroids: Vec<(f32, f32)> = points
+        .iter()
+        .zip(&sums)
+        .map(|(&point, &(sx, sy, w))| {
+            if w > 0.0 {
+                ((point % width) as f32 + (sx / w) as f32, (sy / w) as f32)
+            } else {
+                ((point % width) as f32, (point / width) as f32)
+            }
+        })
+        .collect();
+
+    // Ближайший к центроиду пиксель ячейки (центроид невыпуклой ячейки может лежать вне её)
+    let mut best: Vec<(f32, usize)> = points.iter().map(|&p| (f32::INFINITY, p)).collect();
+    for (idx, &cell) in owner.iter().enumerate() {
+        if cell == usize::MAX {
+            continue;
+        }
+        let (cx, cy) = centroids[cell];
+        let dx = wrapped_dx((idx % width) as f32, cx, width as f32);
+        let dy = (idx / width) as f32 - cy;
+        let distance = dx * dx + dy * dy;
+        if distance < best[cell].0 {
+            best[cell] = (distance, idx);
+        }
+    }
+    for (point, (_, idx)) in points.iter_mut().zip(best) {
+        *point = idx;
+    }
+}
+
+/// Геодезическая диаграмма Вороного: индекс ближайшего семени для каждого пикселя
+///
+/// Пиксели с нулевой плотностью и недостижимые от семян получают `usize::MAX`.
+fn voronoi_cells(width: usize, height: usize, density: &[f32], points: &[usize]) -> Vec<usize> {
+    let mut owner = vec![usize::MAX; density.len()];
+    let mut queue = VecDeque::with_capacity(points.len());
+    for (cell, &idx) in points.iter().enumerate() {
+        if owner[idx] == usize::MAX {
+            owner[idx] = cell;
+            queue.push_back(idx);
+        }
+    }
+
+    while let Some(idx) = queue.pop_front() {
+        let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+        for &(dx, dy) in &DIRECTIONS {
+            let ny = y + dy;
+            if ny < 0 || ny >= height as i32 {
+                continue;
+            }
+            let nx = (x + dx).rem_euclid(width as i32);
+            let nidx = ny as usize * width + nx as usize;
+            if owner[nidx] == usize::MAX && density[nidx] > 0.0 {
+                owner[nidx] = owner[idx];
+                queue.push_back(nidx);
+            }
+        }
+    }
+
+    owner
+}
+
+/// Смещение `a - b` по долготе, приведённое к `[-width/2, width/2]`
+pub(crate) fn wrapped_dx(a: f32, b: f32, width: f32) -> f32 {
+    let dx = (a - b).rem_euclid(width);
+    if dx > width / 2.0 { dx - width } else { dx }
+}
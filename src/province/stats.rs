@@ -0,0 +1,180 @@
+// src/province/stats.rs
+//! Попиксельная статистика провинций
+//!
+//! Этот модуль собирает за **один проход** по карте пиксели каждой провинции,
+//! интересные для анализа: речные, горные и береговые. Раньше каждый анализ
+//! (например, поиск стратегических точек) заново сканировал всю карту
+//! `pixel_to_id` для каждой провинции — O(провинции × пиксели). Пиксели провинций
+//! берутся из пространственного индекса [`ProvinceIndex`].
+//!
+//! ## Собираемые данные
+//!
+//! Для каждой провинции индекса:
+//! - **Площадь** — число пикселей с её `province_id`
+//! - **Речные пиксели** — значение `RiverMap` > 0
+//! - **Горные пиксели** — биомы `RockyMountain` и `GlacialMountain`
+//! - **Береговые пиксели** — суша (`WaterType::Land`), граничащая по одному из
+//!   4 направлений с водой (океаном или озером)
+//!
+//! Списки хранят индексы пикселей (`y * width + x`) в порядке сканирования карты,
+//! поэтому выбор «первого лучшего» пикселя детерминирован.
+//!
+//! ## Сложность
+//! - Построение: O(W × H), один проход по карте
+//! - Доступ к статистике провинции: O(1)
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::biome::{Biome, BiomeMap};
+//! use mapgen::province::index::ProvinceIndex;
+//! use mapgen::province::png::ProvinceMap;
+//! use mapgen::province::stats::ProvinceStats;
+//! use mapgen::province::water::WaterType;
+//! use mapgen::rivers::RiverMap;
+//!
+//! // Карта 4×1: провинция 0 — три пикселя суши, провинция 1 — море
+//! let index = ProvinceIndex::from_province_map(&ProvinceMap::from_pixel_map(4, 1, &[0, 0, 0, 1]));
+//! let water_type = vec![WaterType::Land, WaterType::Land, WaterType::Land, WaterType::Ocean];
+//! let biome_map = BiomeMap {
+//!     width: 4,
+//!     height: 1,
+//!     data: vec![Biome::RockyMountain, Biome::Grassland, Biome::Grassland, Biome::Ocean],
+//! };
+//! let river_map = RiverMap { width: 4, height: 1, data: vec![0, 0, 200, 0] };
+//!
+//! let stats = ProvinceStats::build(&index, &water_type, &river_map, &biome_map);
+//! let land = stats.get(0).unwrap();
+//! assert_eq!(land.area, 3);
+//! assert_eq!(land.mountain_pixels, vec![0]);
+//! assert_eq!(land.river_pixels, vec![2]);
+//! // Долгота бесшовна: пиксель 0 граничит с морским пикселем 3
+//! assert_eq!(land.coastline_pixels, vec![0, 2]);
+//! ```
+
+use crate::biome::{Biome, BiomeMap};
+use crate::province::index::ProvinceIndex;
+use crate::province::water::WaterType;
+use crate::rivers::RiverMap;
+use std::collections::HashMap;
+
+/// 4 ортогональных направления для поиска береговых пикселей
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Попиксельная статистика одной провинции
+///
+/// Все списки содержат индексы пикселей (`y * width + x`) в порядке сканирования.
+#[derive(Debug, Clone, Default)]
+pub struct ProvincePixelStats {
+    /// Площадь провинции в пикселях (по `pixel_to_id`)
+    pub area: usize,
+    /// Пиксели рек (значение `RiverMap` > 0)
+    pub river_pixels: Vec<usize>,
+    /// Пиксели горных биомов (`RockyMountain`, `GlacialMountain`)
+    pub mountain_pixels: Vec<usize>,
+    /// Пиксели береговой линии: суша, граничащая с водой
+    pub coastline_pixels: Vec<usize>,
+}
+
+/// Статистика всех провинций карты, построенная за один проход
+///
+/// См. документацию модуля.
+#[derive(Debug, Clone, Default)]
+pub struct ProvinceStats {
+    /// Ширина карты в пикселях
+    pub width: u32,
+    /// Высота карты в пикселях
+    pub height: u32,
+    /// Статистика провинций в порядке индекса
+    stats: Vec<ProvincePixelStats>,
+    /// `province_id` → индекс в `stats`
+    slots: HashMap<u32, usize>,
+}
+
+impl ProvinceStats {
+    /// Собирает статистику всех провинций за один проход по их пикселям
+    ///
+    /// # Параметры
+    /// * `index` — пространственный индекс провинций (отрезки пикселей каждой провинции)
+    /// * `water_type` — классификация воды для поиска береговой линии
+    /// * `river_map` — карта рек
+    /// * `biome_map` — карта биомов
+    ///
+    /// # Возвращает
+    /// Статистику, доступную по `province_id` через [`ProvinceStats::get`].
+    #[must_use]
+    pub fn build(
+        index: &ProvinceIndex,
+        water_type: &[WaterType],
+        river_map: &RiverMap,
+        biome_map: &BiomeMap,
+    ) -> Self {
+        let width = index.width as usize;
+        let height = index.height as usize;
+        let mut result = Self {
+            width: index.width,
+            height: index.height,
+            ..Self::default()
+        };
+
+        for province in index.iter() {
+            let mut entry = ProvincePixelStats {
+                area: province.area,
+                ..ProvincePixelStats::default()
+            };
+
+            for idx in index.pixels(province.id) {
+                if river_map.data[idx] > 0 {
+                    entry.river_pixels.push(idx);
+                }
+                if matches!(
+                    biome_map.data[idx],
+                    Biome::RockyMountain | Biome::GlacialMountain
+                ) {
+                    entry.mountain_pixels.push(idx);
+                }
+
+                // Береговой пиксель — суша, граничащая с водой (с учётом бесшовности)
+                let (x, y) = (idx % width, idx / width);
+                let touches_water = water_type[idx] == WaterType::Land
+                    && DIRECTIONS.iter().any(|&(dx, dy)| {
+                        let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
+                        let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as usize;
+                        water_type[ny * width + nx] != WaterType::Land
+                    });
+                if touches_water {
+                    entry.coastline_pixels.push(idx);
+                }
+            }
+
+            result.slots.insert(province.id, result.stats.len());
+            result.stats.push(entry);
+        }
+
+        result
+    }
+
+    /// Статистика провинции по её `province_id`
+    #[must_use]
+    pub fn get(&self, province_id: u32) -> Option<&ProvincePixelStats> {
+        self.slots.get(&province_id).map(|&slot| &self.stats[slot])
+    }
+
+    /// Число провинций, встреченных на карте
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stats.len()
+    }
+
+    /// Нет ли ни одной провинции на карте
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stats.is_empty()
+    }
+
+    /// Пиксельные координаты `(x, y)` по индексу пикселя
+    #[must_use]
+    pub fn position(&self, idx: usize) -> (u32, u32) {
+        let width = self.width as usize;
+        ((idx % width) as u32, (idx / width) as u32)
+    }
+}
@@ -52,12 +52,13 @@
 //! ```
 
 use crate::heightmap::Heightmap;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
 /// Тип водной поверхности
 ///
 /// Определяет географическую и геймплейную принадлежность водного пикселя.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WaterType {
     /// Мировой океан — водная масса, соединённая с краями карты
     ///
@@ -51,7 +51,7 @@
 //! ## Пример использования
 //! ```rust
 //! // Построение графа смежности
-//! let graph = build_province_graph_with_map(&provinces, &pixel_to_id, width, height);
+//! let graph = build_province_graph_with_map(&provinces, &pixel_to_id, &biome_map, &river_map);
 //!
 //! // Группировка в регионы (целевой размер = 8 провинций)
 //! let regions = group_provinces_into_regions(&provinces, &graph, 8);
@@ -63,6 +63,7 @@
 
 pub mod png;
 
+use crate::province::graph::ProvinceGraph;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
@@ -182,7 +183,7 @@ fn hash_region_color(region_id: u32) -> String {
 ///
 /// # Параметры
 /// * `provinces` — список всех провинций мира
-/// * `graph` — граф смежности провинций (`UnGraph<u32, ()>`)
+/// * `graph` — граф смежности провинций ([`ProvinceGraph`])
 /// * `target_size` — целевой размер региона в провинциях (рекомендуется 6-12)
 ///
 /// # Возвращает
@@ -224,7 +225,7 @@ fn hash_region_color(region_id: u32) -> String {
 #[must_use]
 pub fn group_provinces_into_regions(
     provinces: &[crate::province::Province],
-    graph: &petgraph::graph::UnGraph<u32, ()>,
+    graph: &ProvinceGraph,
     target_size: usize,
 ) -> Vec<Region> {
     let mut regions = Vec::new();
@@ -37,7 +37,7 @@
 //! ```rust
 //! // Генерация провинций и регионов
 //! let (provinces, pixel_to_id) = generate_provinces_from_seeds(...);
-//! let graph = build_province_graph_with_map(&provinces, &pixel_to_id, width, height);
+//! let graph = build_province_graph_with_map(&provinces, &pixel_to_id, &biome_map, &river_map);
 //! let regions = group_provinces_into_regions(&provinces, &graph, 8);
 //!
 //! // Создание карты регионов
@@ -54,6 +54,8 @@
 
 use std::collections::HashMap;
 
+use crate::error::{MapgenError, image_buffer};
+use crate::province::index::ProvinceIndex;
 use crate::region::Region;
 use image::{ImageBuffer, Rgba};
 
@@ -152,6 +154,60 @@ impl RegionMap {
         }
     }
 
+    /// Создаёт карту регионов по пространственному индексу провинций
+    ///
+    /// В отличие от [`RegionMap::from_pixel_map`], не ищет регион для каждого пикселя:
+    /// отрезки пикселей каждой провинции заполняются её `region_id` целиком.
+    ///
+    /// # Параметры
+    /// * `index` — пространственный индекс провинций
+    /// * `regions` — список регионов для построения маппинга `province_id → region_id`
+    ///
+    /// # Возвращает
+    /// Карту регионов, совпадающую с результатом [`RegionMap::from_pixel_map`]
+    /// (пиксели без провинции и провинции вне регионов получают `region_id = 0`).
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::region::png::RegionMap;
+    /// use mapgen::{WorldGenerationParams, generate_world};
+    ///
+    /// let params = WorldGenerationParams {
+    ///     width: 96,
+    ///     height: 48,
+    ///     ..Default::default()
+    /// };
+    /// let world = generate_world(&params);
+    /// let index = world.province_index();
+    ///
+    /// let map = RegionMap::from_index(&index, &world.regions);
+    /// let expected = RegionMap::from_pixel_map(96, 48, &world.pixel_to_id, &world.regions);
+    /// assert_eq!(map.data, expected.data);
+    /// ```
+    #[must_use]
+    pub fn from_index(index: &ProvinceIndex, regions: &[Region]) -> Self {
+        let width = index.width as usize;
+        let mut data = vec![0; width * index.height as usize];
+
+        for region in regions {
+            for &pid in &region.province_ids {
+                let Some(province) = index.get(pid) else {
+                    continue;
+                };
+                for span in &province.spans {
+                    let row = span.y as usize * width;
+                    data[row + span.x_start as usize..row + span.x_end as usize].fill(region.id);
+                }
+            }
+        }
+
+        Self {
+            width: index.width,
+            height: index.height,
+            data,
+        }
+    }
+
     /// Преобразует карту регионов в RGBA-изображение для визуализации
     ///
     /// # Алгоритм
@@ -221,24 +277,17 @@ impl RegionMap {
     /// * `regions` — список регионов для получения цветов
     ///
     /// # Ошибки
-    /// Возвращает ошибку в случае:
-    /// - Невозможно создать буфер изображения (некорректные размеры)
-    /// - Невозможно записать файл (нет прав, недостаточно места и т.д.)
+    /// - [`MapgenError::InvalidDimensions`] — невозможно создать буфер изображения (некорректные размеры)
+    /// - [`MapgenError::ImageEncoding`] — не удалось закодировать изображение
+    /// - [`MapgenError::Io`] — невозможно записать файл (нет прав, недостаточно места и т.д.)
     ///
     /// # Пример
     /// ```rust
     /// region_map.save_as_png("output/regions.png", &regions)?;
     /// ```
-    pub fn save_as_png(
-        &self,
-        path: &str,
-        regions: &[Region],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save_as_png(&self, path: &str, regions: &[Region]) -> Result<(), MapgenError> {
         let rgba_data = self.to_rgba_image(regions);
-        let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
-            ImageBuffer::from_raw(self.width, self.height, rgba_data)
-                .ok_or("Failed to create image buffer")?;
-        img.save(path)?;
-        Ok(())
+        let img: ImageBuffer<Rgba<u8>, Vec<u8>> = image_buffer(self.width, self.height, rgba_data)?;
+        img.save(path).map_err(|e| MapgenError::image(path, e))
     }
 }
@@ -17,12 +17,14 @@
 //! - Толщина рек пропорциональна объёму воды
 
 use crate::biome::{Biome, BiomeMap};
+use crate::error::{MapgenError, image_buffer};
 use crate::heightmap::Heightmap;
 use image::{ImageBuffer, Luma};
 use imageproc::drawing::draw_filled_circle_mut;
+use serde::{Deserialize, Serialize};
 
 /// Карта рек — распределение гидрографической сети по поверхности мира
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiverMap {
     /// Ширина карты в пикселях
     pub width: u32,
@@ -186,17 +188,17 @@ impl RiverMap {
     /// * `path` — путь к файлу для сохранения
     ///
     /// # Ошибки
-    /// Возвращает ошибку, если не удаётся создать или записать файл.
+    /// - [`MapgenError::InvalidDimensions`] — размер данных не совпадает с размерами карты
+    /// - [`MapgenError::ImageEncoding`] — не удалось закодировать изображение
+    /// - [`MapgenError::Io`] — не удалось записать файл
     ///
     /// # Пример
     /// ```rust
     /// river_map.save_as_png("output/rivers.png")?;
     /// ```
-    pub fn save_as_png(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save_as_png(&self, path: &str) -> Result<(), MapgenError> {
         let img: ImageBuffer<Luma<u8>, Vec<u8>> =
-            ImageBuffer::from_raw(self.width, self.height, self.data.clone())
-                .ok_or("Failed to create image buffer")?;
-        img.save(path)?;
-        Ok(())
+            image_buffer(self.width, self.height, self.data.clone())?;
+        img.save(path).map_err(|e| MapgenError::image(path, e))
     }
 }
@@ -0,0 +1,146 @@
+// src/stage.rs
+//! Этапы конвейера генерации
+//!
+//! Этот модуль описывает этапы генерации мира и зависимости между ними.
+//! Позволяет запускать конвейер частично: вызывающий код выбирает нужные этапы,
+//! а все необходимые вышестоящие этапы добавляются автоматически
+//! ([`Stage::with_dependencies`]).
+//!
+//! ## Граф зависимостей
+//!
+//! ```text
+//! heightmap ─→ climate ─→ biomes ─→ rivers ─→ provinces ─┬─→ strategic
+//!                                                        └─→ regions
+//! ```
+//!
+//! Провинциям нужны реки: рёбра графа смежности отмечают границы, пересекающие реку.
+//! Равномерное размещение семян (`seeding.mode = "relaxed"`) использует карту температуры.
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::stage::Stage;
+//!
+//! let stages = Stage::with_dependencies(&[Stage::Regions]);
+//! assert!(stages.contains(&Stage::Heightmap));
+//! assert!(stages.contains(&Stage::Provinces));
+//! assert!(stages.contains(&Stage::Rivers));
+//! assert!(!stages.contains(&Stage::Strategic));
+//! ```
+
+use crate::error::MapgenError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Этап конвейера генерации
+///
+/// Порядок вариантов совпадает с порядком выполнения этапов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    /// Карта высот и классификация воды
+    Heightmap,
+    /// Температура, ветры и влажность
+    Climate,
+    /// Назначение биомов
+    Biomes,
+    /// Гидрографическая сеть
+    Rivers,
+    /// Провинции и граф смежности
+    Provinces,
+    /// Группировка провинций в регионы
+    Regions,
+    /// Стратегические точки (порты, устья, перевалы)
+    Strategic,
+}
+
+impl Stage {
+    /// Все этапы в порядке выполнения
+    pub const ALL: [Stage; 7] = [
+        Stage::Heightmap,
+        Stage::Climate,
+        Stage::Biomes,
+        Stage::Rivers,
+        Stage::Provinces,
+        Stage::Regions,
+        Stage::Strategic,
+    ];
+
+    /// Имя этапа (используется в CLI и конфигурации)
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Heightmap => "heightmap",
+            Stage::Climate => "climate",
+            Stage::Biomes => "biomes",
+            Stage::Rivers => "rivers",
+            Stage::Provinces => "provinces",
+            Stage::Regions => "regions",
+            Stage::Strategic => "strategic",
+        }
+    }
+
+    /// Непосредственные зависимости этапа
+    #[must_use]
+    pub fn dependencies(self) -> &'static [Stage] {
+        match self {
+            Stage::Heightmap => &[],
+            Stage::Climate => &[Stage::Heightmap],
+            Stage::Biomes => &[Stage::Heightmap, Stage::Climate],
+            Stage::Rivers => &[Stage::Heightmap, Stage::Biomes],
+            Stage::Provinces => &[
+                Stage::Heightmap,
+                Stage::Climate,
+                Stage::Biomes,
+                Stage::Rivers,
+            ],
+            Stage::Regions => &[Stage::Provinces],
+            Stage::Strategic => &[Stage::Biomes, Stage::Rivers, Stage::Provinces],
+        }
+    }
+
+    /// Дополняет набор этапов всеми транзитивными зависимостями
+    ///
+    /// # Возвращает
+    /// Упорядоченное множество этапов, замкнутое относительно зависимостей.
+    #[must_use]
+    pub fn with_dependencies(stages: &[Stage]) -> BTreeSet<Stage> {
+        let mut resolved = BTreeSet::new();
+        let mut stack: Vec<Stage> = stages.to_vec();
+        while let Some(stage) = stack.pop() {
+            if resolved.insert(stage) {
+                stack.extend_from_slice(stage.dependencies());
+            }
+        }
+        resolved
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Stage {
+    type Err = MapgenError;
+
+    /// Разбирает имя этапа без учёта регистра
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::stage::Stage;
+    ///
+    /// assert_eq!("Rivers".parse::<Stage>().unwrap(), Stage::Rivers);
+    /// assert!("erosion".parse::<Stage>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Stage::ALL
+            .into_iter()
+            .find(|stage| stage.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| MapgenError::UnknownStage {
+                name: s.to_string(),
+            })
+    }
+}
@@ -20,14 +20,15 @@
 //! ### Порты (`Port`)
 //! - Условия:
 //!   - Провинция является прибрежной (`province.coastal == true`)
-//!   - Провинция не содержит значимого речного потока
+//!   - Ни одна река провинции не впадает в воду
+//! - Позиция: пиксель береговой линии, ближайший к центру провинции
 //! - Геймплей: базовые морские торговые возможности
 //!
 //! ### Устья рек (`Estuary`)
 //! - Условия:
 //!   - Провинция является прибрежной
-//!   - Провинция содержит пиксели реки (значение > 0 в `RiverMap`)
-//!   - Река впадает в море в пределах провинции
+//!   - Пиксель реки (значение > 0 в `RiverMap`) граничит с водой в пределах провинции
+//! - Позиция: пиксель устья (при нескольких — самый полноводный)
 //! - Геймплей: повышенные бонусы к торговле (доступ к внутренним водным путям)
 //!
 //! ### Перевалы (`Pass`)
@@ -35,30 +36,51 @@
 //!   - Провинция является внутренней (`!province.coastal`)
 //!   - Провинция содержит горные биомы (`RockyMountain` или `GlacialMountain`)
 //!   - Провинция имеет небольшую площадь (< 300 пикселей) — узкий проход
+//! - Позиция: седловина — самый низкий горный пиксель провинции
 //! - Геймплей: естественные укрепления, бонусы к обороне
 //!
 //! ### Проливы (`Strait`)
-//! - Условия (требует анализа морских провинций):
-//!   - Морская провинция окружена сушей с двух сторон
-//!   - Ширина провинции мала относительно длины (высокий аспект-рейтио)
+//! - Условия (по классификации воды `classify_water`):
+//!   - Пиксель океана зажат сушей с двух сторон на расстоянии не больше
+//!     максимальной ширины пролива (`width / 48`, не меньше 3 пикселей),
+//!     а в перпендикулярном направлении вода продолжается
+//!   - Связная группа таких пикселей соединяет две разные акватории: без неё
+//!     вода по обе стороны не сообщается в окрестности пролива
+//!   - Берега принадлежат разным провинциям суши либо акватории — разным морским
+//!     провинциям (узкий перешеек между ними)
+//!   - Обе акватории — морская провинция пролива или её соседи по графу провинций
+//! - Позиция: самое узкое место пролива
+//! - Результат: обе соединяемые морские провинции и обе прибрежные провинции суши
 //! - Геймплей: контроль над морскими путями, возможность блокады
-//! - ⚠️ **В текущей версии не реализовано** — требует анализа формы морских провинций
 //!
 //! ## Ограничения текущей реализации
 //!
-//! - **Простой алгоритм**: обнаружение основано на локальных свойствах провинции
-//!   (не учитывает глобальную топологию, например, "узкость" пролива)
+//! - **Простой алгоритм**: порты, устья и перевалы определяются по локальным
+//!   свойствам провинции (статистика пикселей из [`crate::province::stats`])
 //! - **Нет иерархии**: все точки одного типа имеют равную значимость
 //!   (в будущем можно добавить веса на основе размера/расположения)
-//! - **Проливы не реализованы**: требуют сложного анализа формы морских провинций
+//! - **Только океан**: проливы между озёрами не ищутся
+//!
+//! ## Координаты
+//!
+//! Каждая точка содержит пиксельную позицию `(x, y)` — место для модели гавани,
+//! крепости и т.п. Точки сериализуются в плоском виде для `strategic.json`:
+//!
+//! ```json
+//! { "type": "estuary", "province_id": 12, "x": 431, "y": 208 }
+//! ```
 //!
 //! ## Пример использования
 //! ```rust
+//! let index = ProvinceIndex::from_province_map(&ProvinceMap::from_pixel_map(width, height, &pixel_to_id));
 //! let strategic_points = find_strategic_points(
 //!     &provinces,
 //!     &river_map,
 //!     &biome_map,
-//!     &pixel_to_id,
+//!     &heightmap,
+//!     &water_type,
+//!     &index,
+//!     &graph,
 //! );
 //!
 //! // Подсчёт типов точек
@@ -68,20 +90,35 @@
 //! ```
 
 use crate::biome::BiomeMap;
+use crate::heightmap::Heightmap;
 use crate::province::Province;
+use crate::province::graph::ProvinceGraph;
+use crate::province::index::ProvinceIndex;
+use crate::province::stats::ProvinceStats;
+use crate::province::water::WaterType;
 use crate::rivers::RiverMap;
-use serde::Serialize;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// 4 ортогональных направления для поиска соседних пикселей
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
 
 /// Тип стратегической точки — географически значимая локация
 ///
-/// Каждая точка привязана к провинции и предоставляет уникальные геймплейные преимущества.
-#[derive(Debug, Clone, Serialize)]
+/// Каждая точка привязана к провинции, имеет пиксельную позицию `(x, y)`
+/// и предоставляет уникальные геймплейные преимущества.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum StrategicPoint {
     /// Морской порт — точка морской торговли и высадки
     ///
     /// Условия появления:
     /// - Провинция прибрежная (`coastal == true`)
-    /// - Отсутствуют значимые реки в провинции
+    /// - Реки провинции не впадают в воду
+    ///
+    /// Позиция: пиксель береговой линии, ближайший к центру провинции.
     ///
     /// Геймплейные эффекты:
     /// - Базовый бонус к морской торговле (+10%)
@@ -92,6 +129,10 @@ pub enum StrategicPoint {
     Port {
         /// Идентификатор провинции, содержащей порт
         province_id: u32,
+        /// X-координата пикселя гавани
+        x: u32,
+        /// Y-координата пикселя гавани
+        y: u32,
     },
 
     /// Горный перевал — узкий проход через хребет
@@ -101,6 +142,8 @@ pub enum StrategicPoint {
     /// - Содержит горные биомы (`RockyMountain` или `GlacialMountain`)
     /// - Малая площадь (< 300 пикселей) — узкий проход
     ///
+    /// Позиция: седловина — самый низкий горный пиксель провинции.
+    ///
     /// Геймплейные эффекты:
     /// - Бонус к обороне (+25% к защите)
     /// - Замедление передвижения армий через провинцию
@@ -110,13 +153,19 @@ pub enum StrategicPoint {
     Pass {
         /// Идентификатор провинции, содержащей перевал
         province_id: u32,
+        /// X-координата седловины
+        x: u32,
+        /// Y-координата седловины
+        y: u32,
     },
 
     /// Устье реки — точка впадения реки в море
     ///
     /// Условия появления:
     /// - Провинция прибрежная
-    /// - Содержит пиксели реки (значение > 0 в `RiverMap`)
+    /// - Пиксель реки (значение > 0 в `RiverMap`) граничит с водой
+    ///
+    /// Позиция: пиксель устья (при нескольких — с наибольшим значением `RiverMap`).
     ///
     /// Геймплейные эффекты:
     /// - Повышенный бонус к торговле (+25%)
@@ -127,72 +176,139 @@ pub enum StrategicPoint {
     Estuary {
         /// Идентификатор провинции, содержащей устье
         province_id: u32,
+        /// X-координата пикселя устья
+        x: u32,
+        /// Y-координата пикселя устья
+        y: u32,
     },
 
     /// Морской пролив — узкий проход между сушей
     ///
-    /// Условия появления (в будущих версиях):
-    /// - Морская провинция с высоким аспект-рейтио (длина >> ширина)
-    /// - Окружена сушей с двух сторон
+    /// Условия появления:
+    /// - Узкая полоса океана между двумя берегами
+    /// - Полоса соединяет две акватории, не сообщающиеся поблизости иначе
     ///
-    /// Геймплейные эффекты (планируемые):
+    /// Геймплейные эффекты:
     /// - Контроль над морскими торговыми путями
     /// - Возможность морской блокады
     /// - Стратегическая важность для флота
     ///
     /// Примеры в реальном мире: Гибралтарский пролив, Босфор
     Strait {
-        /// Идентификатор провинции, содержащей пролив
+        /// Идентификатор морской провинции, содержащей самое узкое место пролива
         province_id: u32,
+        /// X-координата самого узкого места пролива
+        x: u32,
+        /// Y-координата самого узкого места пролива
+        y: u32,
+        /// Ширина пролива в самом узком месте (в пикселях)
+        width: u32,
+        /// Морские провинции, которые соединяет пролив (могут совпадать,
+        /// если обе акватории принадлежат одной провинции)
+        seas: [u32; 2],
+        /// Провинции суши на противоположных берегах пролива
+        lands: [u32; 2],
     },
 }
 
+impl StrategicPoint {
+    /// Идентификатор провинции, к которой привязана точка
+    #[must_use]
+    pub fn province_id(&self) -> u32 {
+        match self {
+            Self::Port { province_id, .. }
+            | Self::Pass { province_id, .. }
+            | Self::Estuary { province_id, .. }
+            | Self::Strait { province_id, .. } => *province_id,
+        }
+    }
+
+    /// Пиксельная позиция точки `(x, y)`
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::strategic::StrategicPoint;
+    ///
+    /// let port = StrategicPoint::Port { province_id: 3, x: 120, y: 45 };
+    /// assert_eq!(port.position(), (120, 45));
+    /// assert_eq!(port.province_id(), 3);
+    /// ```
+    #[must_use]
+    pub fn position(&self) -> (u32, u32) {
+        match self {
+            Self::Port { x, y, .. }
+            | Self::Pass { x, y, .. }
+            | Self::Estuary { x, y, .. }
+            | Self::Strait { x, y, .. } => (*x, *y),
+        }
+    }
+}
+
+/// Кандидаты на позицию точки внутри одной провинции
+#[derive(Default)]
+struct PositionCandidates {
+    /// Лучший пиксель устья: `(значение реки, индекс)`
+    mouth: Option<(u8, usize)>,
+    /// Пиксель береговой линии, ближайший к центру: `(квадрат расстояния, индекс)`
+    shore: Option<(f32, usize)>,
+    /// Самый низкий горный пиксель: `(высота, индекс)`
+    saddle: Option<(f32, usize)>,
+}
+
 /// Находит стратегические точки на карте мира
 ///
 /// # Алгоритм
-/// 1. **Итерация по провинциям**:
-///    - Пропускаем морские провинции (пока не поддерживаем проливы)
-///    - Анализируем только сухопутные провинции
+/// 1. **Статистика пикселей**: за один проход по карте строится [`ProvinceStats`] —
+///    береговые и горные пиксели каждой провинции
 ///
-/// 2. **Анализ пикселей провинции**:
-///    - Сканируем все пиксели карты для поиска:
-///      - Пикселей реки (`river_map.data[idx] > 0`)
-///      - Горных биомов (`RockyMountain`, `GlacialMountain`)
-///    - Определяем наличие реки и гор в провинции
+/// 2. **Анализ пикселей сухопутной провинции** (морские пропускаются, проливы
+///    ищутся отдельно, см. шаг 4) — только по спискам из статистики:
+///    - Береговой пиксель реки — кандидат в устье (самый полноводный)
+///    - Береговой пиксель — кандидат в гавань (ближайший к центру)
+///    - Горный пиксель (`RockyMountain`, `GlacialMountain`) — кандидат в седловину
+///      (самый низкий)
 ///
 /// 3. **Классификация точки** (в порядке приоритета):
-///    - **Устье**: прибрежная + есть река
-///    - **Порт**: прибрежная + нет реки
+///    - **Устье**: прибрежная + река впадает в воду
+///    - **Порт**: прибрежная + есть берег
 ///    - **Перевал**: внутренняя + есть горы + малая площадь (< 300)
-///    - **Пролив**: не реализован в текущей версии
+///
+/// 4. **Проливы**: поиск узких полос океана, соединяющих две акватории
+///    (см. документацию модуля)
 ///
 /// # Параметры
 /// * `provinces` — список всех провинций мира
 /// * `river_map` — карта рек для обнаружения речных пикселей
 /// * `biome_map` — карта биомов для обнаружения горных биомов
-/// * `pixel_to_id` — карта пикселей → `province_id` для привязки пикселей к провинциям
+/// * `heightmap` — карта высот для поиска седловин перевалов
+/// * `water_type` — классификация воды для поиска береговой линии, устьев и проливов
+/// * `index` — пространственный индекс провинций (пиксели каждой провинции и
+///   карта пикселей → `province_id`)
+/// * `graph` — граф смежности провинций для проверки соединяемых проливом акваторий
 ///
 /// # Возвращает
-/// Вектор `StrategicPoint` с найденными точками. Порядок не гарантируется.
+/// Вектор `StrategicPoint`: точки суши в порядке провинций, затем проливы.
 ///
 /// # Особенности
-/// - **Приоритет устьев**: прибрежная провинция с рекой всегда классифицируется как устье,
-///   а не как порт (устье ценнее геймплейно)
+/// - **Приоритет устьев**: прибрежная провинция с устьем реки всегда классифицируется
+///   как устье, а не как порт (устье ценнее геймплейно)
 /// - **Перевалы требуют малой площади**: только узкие горные проходы получают статус перевала
-/// - **Проливы не реализованы**: текущая версия возвращает только порты, устья и перевалы
+/// - **Проливы не дублируются**: для каждой пары акваторий и берегов остаётся
+///   только самое узкое место
 /// - **Детерминированность**: результат зависит только от входных данных
+/// - **Сложность**: O(W × H) — карта сканируется один раз, а не для каждой провинции
 ///
 /// # Пример
 /// ```rust
-/// let points = find_strategic_points(&provinces, &river_map, &biome_map, &pixel_to_id);
+/// let points = find_strategic_points(
+///     &provinces, &river_map, &biome_map, &heightmap, &water_type, &index, &graph,
+/// );
 ///
-/// // Найти все порты
-/// let ports: Vec<u32> = points
+/// // Найти все порты и их позиции
+/// let ports: Vec<(u32, (u32, u32))> = points
 ///     .iter()
-///     .filter_map(|p| match p {
-///         StrategicPoint::Port { province_id } => Some(*province_id),
-///         _ => None,
-///     })
+///     .filter(|p| matches!(p, StrategicPoint::Port { .. }))
+///     .map(|p| (p.province_id(), p.position()))
 ///     .collect();
 /// ```
 #[must_use]
@@ -200,64 +316,403 @@ pub fn find_strategic_points(
     provinces: &[Province],
     river_map: &RiverMap,
     biome_map: &BiomeMap,
-    pixel_to_id: &[u32],
+    heightmap: &Heightmap,
+    water_type: &[WaterType],
+    index: &ProvinceIndex,
+    graph: &ProvinceGraph,
 ) -> Vec<StrategicPoint> {
     let mut points = Vec::new();
-    let width = biome_map.width as usize;
-    let height = biome_map.height as usize;
+    let width = index.width as usize;
+    let stats = ProvinceStats::build(index, water_type, river_map, biome_map);
 
-    // Анализируем каждую провинцию
+    // Анализируем каждую провинцию по заранее собранной статистике пикселей
     for province in provinces {
-        // Пропускаем морские провинции (проливы пока не реализованы)
+        // Пропускаем морские провинции (проливы ищутся отдельно)
         if !province.is_land {
             continue;
         }
+        let Some(province_stats) = stats.get(province.id) else {
+            continue;
+        };
 
-        let mut has_river = false;
-        let mut has_mountain = false;
+        let mut candidates = PositionCandidates::default();
 
-        // Сканируем все пиксели провинции
-        for y in 0..height {
-            for x in 0..width {
-                let idx = y * width + x;
-                if pixel_to_id[idx] != province.id {
-                    continue;
-                }
+        for &idx in &province_stats.coastline_pixels {
+            // Береговой пиксель: ближайший к центру (с учётом бесшовности)
+            let (x, y) = stats.position(idx);
+            let dx = (x as f32 - province.center.0).abs();
+            let dx = dx.min(width as f32 - dx);
+            let dy = y as f32 - province.center.1;
+            let distance = dx * dx + dy * dy;
+            if candidates.shore.is_none_or(|(best, _)| distance < best) {
+                candidates.shore = Some((distance, idx));
+            }
 
-                // Проверка наличия реки
-                if river_map.data[idx] > 0 {
-                    has_river = true;
-                }
+            // Устье: река, впадающая в воду (самая полноводная)
+            let flow = river_map.data[idx];
+            if flow > 0 && candidates.mouth.is_none_or(|(best, _)| flow > best) {
+                candidates.mouth = Some((flow, idx));
+            }
+        }
 
-                // Проверка горных биомов
-                match biome_map.data[idx] {
-                    crate::biome::Biome::RockyMountain | crate::biome::Biome::GlacialMountain => {
-                        has_mountain = true;
-                    }
-                    _ => {}
-                }
+        // Седловина: самый низкий горный пиксель
+        for &idx in &province_stats.mountain_pixels {
+            let elevation = heightmap.data[idx];
+            if candidates
+                .saddle
+                .is_none_or(|(lowest, _)| elevation < lowest)
+            {
+                candidates.saddle = Some((elevation, idx));
             }
         }
 
         // Классификация стратегической точки (в порядке приоритета)
-        if province.coastal && has_river {
-            // Устье: прибрежная провинция с рекой (наиболее ценная точка)
+        if let (true, Some((_, idx))) = (province.coastal, candidates.mouth) {
+            // Устье: прибрежная провинция с рекой, впадающей в воду (наиболее ценная точка)
+            let (x, y) = stats.position(idx);
             points.push(StrategicPoint::Estuary {
                 province_id: province.id,
+                x,
+                y,
             });
-        } else if province.coastal {
-            // Порт: прибрежная провинция без реки
+        } else if let (true, Some((_, idx))) = (province.coastal, candidates.shore) {
+            // Порт: прибрежная провинция без устья
+            let (x, y) = stats.position(idx);
             points.push(StrategicPoint::Port {
                 province_id: province.id,
+                x,
+                y,
             });
-        } else if has_mountain && province.area < 300 {
+        } else if let (true, Some((_, idx))) = (province.area < 300, candidates.saddle) {
             // Перевал: внутренняя горная провинция малой площади (узкий проход)
+            let (x, y) = stats.position(idx);
             points.push(StrategicPoint::Pass {
                 province_id: province.id,
+                x,
+                y,
             });
         }
-        // Проливы: требуют анализа морских провинций — не реализованы в текущей версии
     }
 
+    points.extend(find_straits(
+        index.width,
+        index.height,
+        water_type,
+        index.pixel_to_id(),
+        graph,
+    ));
     points
 }
+
+/// Узкое место в океане: самый короткий зажатый сушей поперечник через пиксель
+#[derive(Clone, Copy)]
+struct NarrowSpan {
+    /// Ширина поперечника в пикселях воды
+    width: u32,
+    /// Пиксели суши на концах поперечника
+    shores: [usize; 2],
+}
+
+/// Оси поиска поперечников: `(направление, перпендикуляр)`
+const SPAN_AXES: [((i32, i32), (i32, i32)); 4] = [
+    ((1, 0), (0, 1)),
+    ((0, 1), (1, 0)),
+    ((1, 1), (1, -1)),
+    ((1, -1), (1, 1)),
+];
+
+/// Делитель ширины карты для максимальной ширины пролива
+const STRAIT_WIDTH_DIVISOR: u32 = 48;
+
+/// Минимальное значение максимальной ширины пролива в пикселях
+const MIN_STRAIT_WIDTH: u32 = 3;
+
+/// Находит проливы — узкие полосы океана, соединяющие две акватории
+///
+/// # Алгоритм
+/// 1. **Узкие пиксели**: для каждого пикселя океана ищется поперечник (по горизонтали,
+///    вертикали или диагонали), зажатый сушей с двух сторон и не шире
+///    `max_width`, при том что в перпендикулярном направлении вода тянется дальше
+/// 2. **Группировка**: узкие пиксели объединяются в связные группы (8 соседей)
+/// 3. **Проверка связности**: от воды, граничащей с группой, выполняется
+///    ограниченный по глубине BFS в обход группы; если граничащая вода распадается
+///    на несколько несообщающихся акваторий — группа является проливом
+/// 4. **Описание**: самое узкое место, две крупнейшие акватории (их преобладающие
+///    морские провинции) и провинции суши на концах поперечника
+/// 5. **Отбор**: берега принадлежат разным провинциям суши или акватории — разным
+///    морским провинциям; обе акватории — провинция пролива или её соседи в графе
+///    смежности
+///
+/// # Возвращает
+/// Вектор `StrategicPoint::Strait`, по одному на пару акваторий и берегов.
+fn find_straits(
+    width: u32,
+    height: u32,
+    water_type: &[WaterType],
+    pixel_to_id: &[u32],
+    graph: &ProvinceGraph,
+) -> Vec<StrategicPoint> {
+    let grid = OceanGrid {
+        width: width as usize,
+        height: height as usize,
+        water_type,
+    };
+    let max_width = (width / STRAIT_WIDTH_DIVISOR).max(MIN_STRAIT_WIDTH);
+
+    // ШАГ 1: Узкие пиксели океана
+    let narrow: HashMap<usize, NarrowSpan> = (0..grid.width * grid.height)
+        .filter_map(|idx| grid.narrow_span(idx, max_width).map(|span| (idx, span)))
+        .collect();
+
+    let id_to_node: HashMap<u32, NodeIndex> = graph.node_indices().map(|n| (graph[n], n)).collect();
+    let is_neighbour = |a: u32, b: u32| {
+        a == b
+            || matches!(
+                (id_to_node.get(&a), id_to_node.get(&b)),
+                (Some(&na), Some(&nb)) if graph.contains_edge(na, nb)
+            )
+    };
+
+    // ШАГ 2: Связные группы узких пикселей
+    let mut starts: Vec<usize> = narrow.keys().copied().collect();
+    starts.sort_unstable();
+    let mut visited: HashSet<usize> = HashSet::new();
+    let mut straits: HashMap<([u32; 2], [u32; 2]), StrategicPoint> = HashMap::new();
+    for start in starts {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut i = 0;
+        while i < component.len() {
+            let idx = component[i];
+            i += 1;
+            for (dx, dy) in (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy))) {
+                if let Some(next) = grid.step(idx, (dx, dy))
+                    && narrow.contains_key(&next)
+                    && visited.insert(next)
+                {
+                    component.push(next);
+                }
+            }
+        }
+
+        // ШАГ 3: Акватории по обе стороны группы
+        let Some(sides) = grid.split_waters(&component, max_width * 4) else {
+            continue;
+        };
+
+        // ШАГ 4: Описание пролива по самому узкому месту
+        let Some(&narrowest) = component
+            .iter()
+            .min_by_key(|&&idx| (narrow[&idx].width, idx))
+        else {
+            continue;
+        };
+        let span = narrow[&narrowest];
+        let province_id = pixel_to_id[narrowest];
+        let seas = sides.map(|side| dominant_province(&side, pixel_to_id));
+        let lands = span.shores.map(|idx| pixel_to_id[idx]);
+
+        // ШАГ 5: Пролив разделяет две провинции суши или соединяет две морские провинции,
+        // и обе акватории примыкают к провинции пролива
+        if (lands[0] == lands[1] && seas[0] == seas[1])
+            || !seas.iter().all(|&sea| is_neighbour(province_id, sea))
+        {
+            continue;
+        }
+
+        let mut key = (seas, lands);
+        key.0.sort_unstable();
+        key.1.sort_unstable();
+        let point = StrategicPoint::Strait {
+            province_id,
+            x: (narrowest % grid.width) as u32,
+            y: (narrowest / grid.width) as u32,
+            width: span.width,
+            seas,
+            lands,
+        };
+        let is_narrower = |existing: &StrategicPoint| match existing {
+            StrategicPoint::Strait { width, .. } => span.width < *width,
+            _ => false,
+        };
+        if straits.get(&key).is_none_or(is_narrower) {
+            straits.insert(key, point);
+        }
+    }
+
+    let mut straits: Vec<StrategicPoint> = straits.into_values().collect();
+    straits.sort_by_key(|p| (p.province_id(), p.position().1, p.position().0));
+    straits
+}
+
+/// Бесшовная по долготе сетка классификации воды для поиска проливов
+struct OceanGrid<'a> {
+    width: usize,
+    height: usize,
+    water_type: &'a [WaterType],
+}
+
+impl OceanGrid<'_> {
+    /// Является ли пиксель океаном
+    fn is_ocean(&self, idx: usize) -> bool {
+        self.water_type[idx] == WaterType::Ocean
+    }
+
+    /// Соседний пиксель в направлении `(dx, dy)` (`None` за полюсом)
+    fn step(&self, idx: usize, (dx, dy): (i32, i32)) -> Option<usize> {
+        let ny = (idx / self.width) as i32 + dy;
+        if ny < 0 || ny >= self.height as i32 {
+            return None;
+        }
+        let nx = ((idx % self.width) as i32 + dx).rem_euclid(self.width as i32) as usize;
+        Some(ny as usize * self.width + nx)
+    }
+
+    /// Проход по воде в направлении `dir` не дальше `limit` пикселей
+    ///
+    /// # Возвращает
+    /// Кортеж `(число пройденных пикселей воды, пиксель суши, если он встречен)`.
+    fn walk(&self, idx: usize, dir: (i32, i32), limit: u32) -> (u32, Option<usize>) {
+        let mut current = idx;
+        for count in 0..limit {
+            match self.step(current, dir) {
+                Some(next) if self.is_ocean(next) => current = next,
+                Some(next) => return (count, Some(next)),
+                None => return (count, None),
+            }
+        }
+        (limit, None)
+    }
+
+    /// Самый узкий поперечник через пиксель океана, если пиксель лежит в проливе
+    ///
+    /// Поперечник должен быть зажат сушей с обеих сторон и не шире `max_width`,
+    /// а в перпендикулярном направлении вода должна тянуться не меньше `max_width`.
+    /// Проходы ограничены оставшимся запасом ширины и обрываются, как только
+    /// поперечник не может стать уже найденного, поэтому в открытом море на пиксель
+    /// приходится не больше одного прохода длины `max_width` по каждой оси.
+    fn narrow_span(&self, idx: usize, max_width: u32) -> Option<NarrowSpan> {
+        if !self.is_ocean(idx) {
+            return None;
+        }
+        let mut best: Option<NarrowSpan> = None;
+        for &(axis, normal) in &SPAN_AXES {
+            // Допустимая ширина: не больше `max_width` и строго уже найденного
+            let budget = best.map_or(max_width, |n| n.width - 1);
+            if budget == 0 {
+                break;
+            }
+            let (ahead, Some(a)) = self.walk(idx, axis, budget) else {
+                continue;
+            };
+            let (behind, Some(b)) = self.walk(idx, (-axis.0, -axis.1), budget - ahead) else {
+                continue;
+            };
+            // Вдоль пролива вода должна продолжаться
+            let forward = self.walk(idx, normal, max_width).0;
+            let backward = self
+                .walk(idx, (-normal.0, -normal.1), max_width - forward)
+                .0;
+            if forward + backward >= max_width {
+                best = Some(NarrowSpan {
+                    width: ahead + behind + 1,
+                    shores: [a, b],
+                });
+            }
+        }
+        best
+    }
+
+    /// Делит воду, граничащую с группой узких пикселей, на несообщающиеся акватории
+    ///
+    /// BFS из пикселей океана, соседних с группой, в обход самой группы; глубина
+    /// ограничена `radius`. Фронты, встретившиеся в пределах радиуса, объединяются.
+    ///
+    /// # Возвращает
+    /// Пиксели двух крупнейших акваторий или `None`, если вода вокруг группы сообщается.
+    fn split_waters(&self, component: &[usize], radius: u32) -> Option<[Vec<usize>; 2]> {
+        let blocked: HashSet<usize> = component.iter().copied().collect();
+        let mut label: HashMap<usize, usize> = HashMap::new();
+        let mut parent: Vec<usize> = Vec::new();
+        let mut queue = VecDeque::new();
+
+        // Затравки: вода, граничащая с группой
+        for &idx in component {
+            for &dir in &DIRECTIONS {
+                if let Some(next) = self.step(idx, dir)
+                    && self.is_ocean(next)
+                    && !blocked.contains(&next)
+                    && !label.contains_key(&next)
+                {
+                    label.insert(next, parent.len());
+                    parent.push(parent.len());
+                    queue.push_back((next, 0));
+                }
+            }
+        }
+
+        // Ограниченный BFS с объединением встретившихся фронтов
+        while let Some((idx, depth)) = queue.pop_front() {
+            let root = find_root(&mut parent, label[&idx]);
+            for &dir in &DIRECTIONS {
+                let Some(next) = self.step(idx, dir) else {
+                    continue;
+                };
+                if !self.is_ocean(next) || blocked.contains(&next) {
+                    continue;
+                }
+                match label.get(&next) {
+                    Some(&other) => {
+                        let other = find_root(&mut parent, other);
+                        if other != root {
+                            parent[other] = root;
+                        }
+                    }
+                    None if depth < radius => {
+                        label.insert(next, root);
+                        queue.push_back((next, depth + 1));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        // Группировка по акваториям
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (&idx, &l) in &label {
+            let root = find_root(&mut parent, l);
+            groups.entry(root).or_default().push(idx);
+        }
+        if groups.len() < 2 {
+            return None;
+        }
+        let mut groups: Vec<Vec<usize>> = groups.into_values().collect();
+        groups.sort_by_key(|g| (Reverse(g.len()), g.iter().min().copied()));
+        let second = groups.swap_remove(1);
+        let first = groups.swap_remove(0);
+        Some([first, second])
+    }
+}
+
+/// Корень множества в системе непересекающихся множеств (со сжатием пути)
+fn find_root(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+/// Преобладающая провинция среди пикселей (при равенстве — с меньшим `id`)
+fn dominant_province(pixels: &[usize], pixel_to_id: &[u32]) -> u32 {
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for &idx in pixels {
+        *counts.entry(pixel_to_id[idx]).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(id, count)| (count, Reverse(id)))
+        .map_or(u32::MAX, |(id, _)| id)
+}
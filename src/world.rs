@@ -0,0 +1,755 @@
+// src/world.rs
+//! Полный конвейер генерации мира
+//!
+//! Этот модуль объединяет все этапы генерации в единую библиотечную точку входа
+//! [`generate_world`], которая возвращает структуру [`World`] со всеми промежуточными
+//! и итоговыми данными. Для частичной генерации (только выбранные этапы и их
+//! зависимости) служит [`generate_partial_world`], а для продолжения генерации
+//! поверх готовой (например, отредактированной вручную) карты высот —
+//! [`generate_partial_world_from_heightmap`].
+//!
+//! ## Конвейер генерации
+//!
+//! 1. **Карта высот** — процедурный рельеф с эрозией (`heightmap`)
+//! 2. **Климат** — температура, ветры и влажность (`climate`)
+//! 3. **Биомы** — классификация ландшафтов (`biome`)
+//! 4. **Вода** — разделение на океаны и озёра (`province::water`)
+//! 5. **Реки** — гидрологическое моделирование (`rivers`)
+//! 6. **Провинции** — семена, рост и слияние мелких провинций (`province`)
+//! 7. **Регионы** — группировка провинций (`region`)
+//! 8. **Стратегические точки** — порты, устья, перевалы (`strategic`)
+//!
+//! ## Особенности
+//!
+//! - **Без побочных эффектов**: модуль ничего не пишет на диск — экспорт остаётся
+//!   задачей вызывающего кода (CLI, игровой сервер, WASM-демо)
+//! - **Детерминированность**: одинаковые параметры дают идентичный мир
+//! - **Владение данными**: `World` владеет всеми слоями, поэтому их можно свободно
+//!   передавать между потоками и сохранять
+//!
+//! ## Пример использования
+//! ```rust
+//! use mapgen::{WorldGenerationParams, generate_world};
+//!
+//! let params = WorldGenerationParams {
+//!     seed: 42,
+//!     width: 128,
+//!     height: 64,
+//!     ..Default::default()
+//! };
+//!
+//! let world = generate_world(&params);
+//! assert_eq!(world.pixel_to_id.len(), 128 * 64);
+//! assert!(!world.provinces.is_empty());
+//! ```
+
+use crate::biome::{BiomeMap, assign_biomes};
+use crate::climate::{calculate_humidity, generate_climate_maps};
+use crate::config::WorldGenerationParams;
+use crate::heightmap::{Heightmap, generate_heightmap};
+use crate::naval::{SeaLaneNetwork, build_sea_lanes};
+use crate::pathfinding::{MovementCosts, PathGrid, ProvincePath, find_province_path};
+use crate::province::Province;
+use crate::province::area::{AreaReport, balance_province_areas};
+use crate::province::generator::{generate_province_seeds, split_province_counts};
+use crate::province::graph::{ProvinceGraph, build_province_graph_with_map};
+use crate::province::growth::{generate_provinces_balanced, grow_provinces};
+use crate::province::index::ProvinceIndex;
+use crate::province::merge::merge_small_provinces;
+use crate::province::png::ProvinceMap;
+use crate::province::seeding::{SeedingMode, generate_relaxed_seeds};
+use crate::province::water::{WaterType, classify_water};
+use crate::region::{Region, group_provinces_into_regions};
+use crate::rivers::{RiverMap, generate_rivers};
+use crate::stage::Stage;
+use crate::strategic::{StrategicPoint, find_strategic_points};
+use serde::{Deserialize, Serialize};
+
+/// Сгенерированный мир — все слои и данные одного запуска генератора
+///
+/// Все векторные слои имеют размер `width × height` и индексируются как `y * width + x`.
+/// Мир сохраняется в единый файл и загружается обратно через `World::save`
+/// и `World::load` (см. модуль [`crate::bundle`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct World {
+    /// Параметры, с которыми был сгенерирован мир
+    pub params: WorldGenerationParams,
+
+    /// Карта высот (0.0–1.0)
+    pub heightmap: Heightmap,
+
+    /// Карта температуры (0.0 = полюс, 1.0 = экватор)
+    pub temperature: Vec<f32>,
+
+    /// Карта влажности (0.0 = пустыня, 1.0 = тропический лес)
+    pub humidity: Vec<f32>,
+
+    /// Карта биомов
+    pub biome_map: BiomeMap,
+
+    /// Классификация воды (океан/озеро/суша) для каждого пикселя
+    pub water_type: Vec<WaterType>,
+
+    /// Гидрографическая сеть
+    pub river_map: RiverMap,
+
+    /// Провинции мира (после слияния мелких провинций)
+    pub provinces: Vec<Province>,
+
+    /// Карта пикселей → `province_id`
+    pub pixel_to_id: Vec<u32>,
+
+    /// Граф смежности провинций (узлы содержат `province_id`, рёбра — атрибуты границ)
+    #[serde(
+        serialize_with = "crate::bundle::serialize_graph",
+        deserialize_with = "crate::bundle::deserialize_graph"
+    )]
+    pub graph: ProvinceGraph,
+
+    /// Регионы — группы соседних провинций одного типа поверхности
+    pub regions: Vec<Region>,
+
+    /// Стратегические точки (порты, устья, перевалы)
+    pub strategic_points: Vec<StrategicPoint>,
+}
+
+impl World {
+    /// Ширина мира в пикселях
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.heightmap.width
+    }
+
+    /// Высота мира в пикселях
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.heightmap.height
+    }
+
+    /// Доля суши в мире (0.0–1.0)
+    ///
+    /// Озёра не считаются сушей — учитываются только пиксели `WaterType::Land`.
+    #[must_use]
+    pub fn land_ratio(&self) -> f32 {
+        if self.water_type.is_empty() {
+            return 0.0;
+        }
+        let land = self
+            .water_type
+            .iter()
+            .filter(|&&t| t == WaterType::Land)
+            .count();
+        land as f32 / self.water_type.len() as f32
+    }
+
+    /// Строит пространственный индекс провинций мира
+    ///
+    /// Индекс отвечает на вопрос «какая провинция в точке (x, y)» за O(1)
+    /// и хранит пиксели, границы и ограничивающие прямоугольники провинций.
+    /// Построение — один проход по карте, поэтому индекс стоит строить один раз
+    /// и переиспользовать.
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::{WorldGenerationParams, generate_world};
+    ///
+    /// let params = WorldGenerationParams {
+    ///     width: 96,
+    ///     height: 48,
+    ///     ..Default::default()
+    /// };
+    /// let world = generate_world(&params);
+    /// let index = world.province_index();
+    ///
+    /// let id = index.province_at(10, 20).unwrap();
+    /// assert_eq!(id, world.pixel_to_id[20 * 96 + 10]);
+    /// assert!(index.get(id).unwrap().bounding_box.contains(10, 20, 96));
+    /// ```
+    #[must_use]
+    pub fn province_index(&self) -> ProvinceIndex {
+        ProvinceIndex::from_province_map(&ProvinceMap::from_pixel_map(
+            self.width(),
+            self.height(),
+            &self.pixel_to_id,
+        ))
+    }
+
+    /// Распределение площадей провинций по типам
+    ///
+    /// См. [`AreaReport`].
+    #[must_use]
+    pub fn area_report(&self) -> AreaReport {
+        AreaReport::from_provinces(&self.provinces)
+    }
+
+    /// Строит сетку стоимостей для пиксельного поиска путей
+    ///
+    /// Сетку стоит строить один раз на режим перемещения и переиспользовать
+    /// для всех запросов (см. [`PathGrid::find_path`]).
+    #[must_use]
+    pub fn path_grid(&self, settings: &MovementCosts) -> PathGrid {
+        PathGrid::new(
+            &self.biome_map,
+            &self.river_map,
+            &self.water_type,
+            &self.pixel_to_id,
+            settings,
+        )
+    }
+
+    /// Ищет маршрут между провинциями по графу смежности мира
+    ///
+    /// См. [`find_province_path`].
+    ///
+    /// # Пример
+    /// ```rust
+    /// use mapgen::pathfinding::MovementCosts;
+    /// use mapgen::{WorldGenerationParams, generate_world};
+    ///
+    /// let params = WorldGenerationParams {
+    ///     width: 96,
+    ///     height: 48,
+    ///     ..Default::default()
+    /// };
+    /// let world = generate_world(&params);
+    ///
+    /// // Армия не может дойти из провинции суши в морскую провинцию
+    /// let land = world.provinces.iter().find(|p| p.is_land).unwrap().id;
+    /// let sea = world.provinces.iter().find(|p| !p.is_land).unwrap().id;
+    /// assert!(world.find_province_path(land, sea, &MovementCosts::default()).is_none());
+    ///
+    /// // Путь в саму себя бесплатен
+    /// let path = world.find_province_path(land, land, &MovementCosts::default()).unwrap();
+    /// assert_eq!(path.provinces, vec![land]);
+    /// assert_eq!(path.cost, 0.0);
+    /// ```
+    #[must_use]
+    pub fn find_province_path(
+        &self,
+        from: u32,
+        to: u32,
+        settings: &MovementCosts,
+    ) -> Option<ProvincePath> {
+        find_province_path(
+            &self.provinces,
+            &self.graph,
+            self.width(),
+            from,
+            to,
+            settings,
+        )
+    }
+
+    /// Строит сеть морских путей между портами мира
+    ///
+    /// См. [`build_sea_lanes`].
+    #[must_use]
+    pub fn sea_lanes(&self) -> SeaLaneNetwork {
+        build_sea_lanes(
+            &self.provinces,
+            &self.pixel_to_id,
+            &self.biome_map,
+            &self.water_type,
+            &self.graph,
+            &self.strategic_points,
+        )
+    }
+}
+
+/// Частично сгенерированный мир — результат [`generate_partial_world`]
+///
+/// Поля соответствуют полям [`World`]; слои этапов, которые не выполнялись,
+/// равны `None`.
+#[derive(Debug, Clone)]
+pub struct PartialWorld {
+    /// Параметры, с которыми был сгенерирован мир
+    pub params: WorldGenerationParams,
+
+    /// Карта высот (этап `heightmap`)
+    pub heightmap: Option<Heightmap>,
+
+    /// Карта температуры (этап `climate`)
+    pub temperature: Option<Vec<f32>>,
+
+    /// Карта влажности (этап `climate`)
+    pub humidity: Option<Vec<f32>>,
+
+    /// Карта биомов (этап `biomes`)
+    pub biome_map: Option<BiomeMap>,
+
+    /// Классификация воды (этап `heightmap`)
+    pub water_type: Option<Vec<WaterType>>,
+
+    /// Гидрографическая сеть (этап `rivers`)
+    pub river_map: Option<RiverMap>,
+
+    /// Провинции мира (этап `provinces`)
+    pub provinces: Option<Vec<Province>>,
+
+    /// Карта пикселей → `province_id` (этап `provinces`)
+    pub pixel_to_id: Option<Vec<u32>>,
+
+    /// Граф смежности провинций (этап `provinces`)
+    pub graph: Option<ProvinceGraph>,
+
+    /// Регионы (этап `regions`)
+    pub regions: Option<Vec<Region>>,
+
+    /// Стратегические точки (этап `strategic`)
+    pub strategic_points: Option<Vec<StrategicPoint>>,
+}
+
+impl PartialWorld {
+    /// Доля суши в мире (0.0–1.0), если выполнен этап карты высот
+    #[must_use]
+    pub fn land_ratio(&self) -> Option<f32> {
+        let water_type = self.water_type.as_ref()?;
+        let land = water_type.iter().filter(|&&t| t == WaterType::Land).count();
+        Some(land as f32 / water_type.len().max(1) as f32)
+    }
+
+    /// Преобразует в полный мир, если выполнены все этапы
+    ///
+    /// # Ошибки
+    /// Если какой-либо слой отсутствует, возвращает исходный частичный мир без изменений.
+    #[allow(clippy::result_large_err)] // частичный мир возвращается перемещением, без копий слоёв
+    pub fn into_world(self) -> Result<World, Self> {
+        match self {
+            PartialWorld {
+                params,
+                heightmap: Some(heightmap),
+                temperature: Some(temperature),
+                humidity: Some(humidity),
+                biome_map: Some(biome_map),
+                water_type: Some(water_type),
+                river_map: Some(river_map),
+                provinces: Some(provinces),
+                pixel_to_id: Some(pixel_to_id),
+                graph: Some(graph),
+                regions: Some(regions),
+                strategic_points: Some(strategic_points),
+            } => Ok(World {
+                params,
+                heightmap,
+                temperature,
+                humidity,
+                biome_map,
+                water_type,
+                river_map,
+                provinces,
+                pixel_to_id,
+                graph,
+                regions,
+                strategic_points,
+            }),
+            partial => Err(partial),
+        }
+    }
+}
+
+impl From<World> for PartialWorld {
+    fn from(world: World) -> Self {
+        Self {
+            params: world.params,
+            heightmap: Some(world.heightmap),
+            temperature: Some(world.temperature),
+            humidity: Some(world.humidity),
+            biome_map: Some(world.biome_map),
+            water_type: Some(world.water_type),
+            river_map: Some(world.river_map),
+            provinces: Some(world.provinces),
+            pixel_to_id: Some(world.pixel_to_id),
+            graph: Some(world.graph),
+            regions: Some(world.regions),
+            strategic_points: Some(world.strategic_points),
+        }
+    }
+}
+
+/// Генерирует полный мир по заданным параметрам
+///
+/// Выполняет весь конвейер генерации от карты высот до стратегических точек
+/// и возвращает структуру [`World`], владеющую всеми результатами.
+///
+/// # Алгоритм
+/// 1. Генерация карты высот и удаление островов меньше `islands.min_island_size`
+/// 2. Расчёт температуры, ветров и влажности
+/// 3. Назначение биомов
+/// 4. Классификация воды и генерация рек
+/// 5. Распределение провинций между сушей и морем с учётом `sea_province_scale`,
+///    генерация семян и рост провинций
+/// 6. Слияние мелких провинций и построение графа смежности
+/// 7. Группировка провинций примерно в `num_regions` регионов
+/// 8. Поиск стратегических точек
+///
+/// Параметры используются как есть: климат и рельеф пресета мира подставляются
+/// при загрузке конфигурации (`config::layers`) или через
+/// [`WorldGenerationParams::for_world_type`].
+///
+/// # Параметры
+/// * `params` — параметры генерации мира
+///
+/// # Возвращает
+/// Структуру `World` со всеми слоями и данными мира.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::{WorldGenerationParams, WorldType, generate_world};
+///
+/// let params = WorldGenerationParams {
+///     seed: 7,
+///     width: 128,
+///     height: 64,
+///     ..WorldGenerationParams::for_world_type(WorldType::Archipelago)
+/// };
+///
+/// let world = generate_world(&params);
+/// let covered: usize = world.regions.iter().map(|r| r.province_ids.len()).sum();
+/// assert_eq!(covered, world.provinces.len());
+/// ```
+#[must_use]
+pub fn generate_world(params: &WorldGenerationParams) -> World {
+    match run_stages(params.clone(), None, &Stage::ALL).into_world() {
+        Ok(world) => world,
+        Err(_) => unreachable!("при генерации всех этапов заполняются все слои мира"),
+    }
+}
+
+/// Генерирует только выбранные этапы мира
+///
+/// Все вышестоящие этапы, необходимые выбранным, добавляются автоматически
+/// (см. [`Stage::with_dependencies`]). Слои невыполненных этапов остаются `None`.
+///
+/// # Параметры
+/// * `params` — параметры генерации мира
+/// * `stages` — требуемые этапы
+///
+/// # Возвращает
+/// Структуру [`PartialWorld`] со слоями выполненных этапов.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::{Stage, WorldGenerationParams, generate_partial_world};
+///
+/// let params = WorldGenerationParams {
+///     width: 128,
+///     height: 64,
+///     ..Default::default()
+/// };
+///
+/// let world = generate_partial_world(&params, &[Stage::Biomes]);
+/// assert!(world.heightmap.is_some() && world.temperature.is_some());
+/// assert!(world.biome_map.is_some());
+/// assert!(world.river_map.is_none() && world.provinces.is_none());
+/// ```
+#[must_use]
+pub fn generate_partial_world(params: &WorldGenerationParams, stages: &[Stage]) -> PartialWorld {
+    run_stages(params.clone(), None, stages)
+}
+
+/// Генерирует выбранные этапы мира поверх готовой карты высот
+///
+/// Генерация шума и эрозия пропускаются: этап `heightmap` лишь классифицирует воду
+/// относительно `params.sea_level`. Карта используется как есть (мелкие острова не
+/// удаляются), поэтому ручные правки сохраняются. Размеры мира берутся из карты
+/// и заменяют `params.width` и `params.height`.
+///
+/// # Параметры
+/// * `params` — параметры генерации мира
+/// * `heightmap` — готовая карта высот (например, из [`Heightmap::load_png`])
+/// * `stages` — требуемые этапы
+///
+/// # Возвращает
+/// Структуру [`PartialWorld`] с переданной картой высот и слоями выполненных этапов.
+///
+/// # Пример
+/// ```rust
+/// use mapgen::heightmap::Heightmap;
+/// use mapgen::{Stage, WorldGenerationParams, generate_partial_world_from_heightmap};
+///
+/// let mut heightmap = Heightmap::new(96, 48);
+/// for x in 20..60 {
+///     for y in 10..40 {
+///         heightmap.set(x, y, 0.7);
+///     }
+/// }
+///
+/// let world = generate_partial_world_from_heightmap(
+///     &WorldGenerationParams::default(),
+///     heightmap,
+///     &[Stage::Biomes],
+/// );
+/// assert_eq!((world.params.width, world.params.height), (96, 48));
+/// assert!(world.land_ratio().unwrap() > 0.2);
+/// assert!(world.biome_map.is_some());
+/// ```
+#[must_use]
+pub fn generate_partial_world_from_heightmap(
+    params: &WorldGenerationParams,
+    heightmap: Heightmap,
+    stages: &[Stage],
+) -> PartialWorld {
+    let mut params = params.clone();
+    params.width = heightmap.width;
+    params.height = heightmap.height;
+    run_stages(params, Some(heightmap), stages)
+}
+
+/// Выполняет выбранные этапы и их зависимости
+///
+/// Если карта высот передана, этап `heightmap` использует её вместо генерации.
+#[allow(clippy::too_many_lines)] // линейная последовательность этапов, по блоку на этап
+fn run_stages(
+    params: WorldGenerationParams,
+    supplied_heightmap: Option<Heightmap>,
+    stages: &[Stage],
+) -> PartialWorld {
+    let stages = Stage::with_dependencies(stages);
+    let mut world = PartialWorld {
+        params,
+        heightmap: None,
+        temperature: None,
+        humidity: None,
+        biome_map: None,
+        water_type: None,
+        river_map: None,
+        provinces: None,
+        pixel_to_id: None,
+        graph: None,
+        regions: None,
+        strategic_points: None,
+    };
+    let params = &world.params;
+
+    if let Some(heightmap) = supplied_heightmap {
+        world.water_type = Some(classify_water(&heightmap, params.sea_level));
+        world.heightmap = Some(heightmap);
+    } else if stages.contains(&Stage::Heightmap) {
+        let (heightmap, water_type) = run_heightmap(params);
+        world.heightmap = Some(heightmap);
+        world.water_type = Some(water_type);
+    }
+    if stages.contains(&Stage::Climate)
+        && let Some(heightmap) = &world.heightmap
+    {
+        let (temperature, humidity) = run_climate(params, heightmap);
+        world.temperature = Some(temperature);
+        world.humidity = Some(humidity);
+    }
+    if stages.contains(&Stage::Biomes)
+        && let (Some(heightmap), Some(temperature), Some(humidity)) =
+            (&world.heightmap, &world.temperature, &world.humidity)
+    {
+        world.biome_map = Some(assign_biomes(
+            heightmap,
+            temperature,
+            humidity,
+            params.sea_level,
+        ));
+    }
+    if stages.contains(&Stage::Rivers)
+        && let (Some(heightmap), Some(biome_map)) = (&world.heightmap, &world.biome_map)
+    {
+        world.river_map = Some(generate_rivers(heightmap, biome_map));
+    }
+    if stages.contains(&Stage::Provinces)
+        && let (
+            Some(heightmap),
+            Some(biome_map),
+            Some(temperature),
+            Some(water_type),
+            Some(river_map),
+        ) = (
+            &world.heightmap,
+            &world.biome_map,
+            &world.temperature,
+            &world.water_type,
+            &world.river_map,
+        )
+    {
+        let (provinces, pixel_to_id, graph) = run_provinces(
+            params,
+            heightmap,
+            biome_map,
+            temperature,
+            water_type,
+            river_map,
+        );
+        world.provinces = Some(provinces);
+        world.pixel_to_id = Some(pixel_to_id);
+        world.graph = Some(graph);
+    }
+    if stages.contains(&Stage::Regions)
+        && let (Some(provinces), Some(graph)) = (&world.provinces, &world.graph)
+    {
+        world.regions = Some(run_regions(params, provinces, graph));
+    }
+    if stages.contains(&Stage::Strategic)
+        && let (
+            Some(provinces),
+            Some(river_map),
+            Some(biome_map),
+            Some(heightmap),
+            Some(water_type),
+            Some(pixel_to_id),
+            Some(graph),
+        ) = (
+            &world.provinces,
+            &world.river_map,
+            &world.biome_map,
+            &world.heightmap,
+            &world.water_type,
+            &world.pixel_to_id,
+            &world.graph,
+        )
+    {
+        let index = ProvinceIndex::from_province_map(&ProvinceMap::from_pixel_map(
+            heightmap.width,
+            heightmap.height,
+            pixel_to_id,
+        ));
+        world.strategic_points = Some(find_strategic_points(
+            provinces, river_map, biome_map, heightmap, water_type, &index, graph,
+        ));
+    }
+
+    world
+}
+
+/// Этап карты высот: рельеф, удаление мелких островов и классификация воды
+fn run_heightmap(params: &WorldGenerationParams) -> (Heightmap, Vec<WaterType>) {
+    let mut heightmap = generate_heightmap(
+        params.seed,
+        params.width,
+        params.height,
+        &params.preset(),
+        params.islands.island_density,
+        params.sea_level,
+        params.land_ratio(),
+        &params.terrain,
+    );
+    heightmap.remove_small_islands(params.sea_level, params.islands.min_island_size as usize);
+    let water_type = classify_water(&heightmap, params.sea_level);
+    (heightmap, water_type)
+}
+
+/// Этап климата: температура, ветры и влажность
+///
+/// # Возвращает
+/// Кортеж `(температура, влажность)`.
+fn run_climate(params: &WorldGenerationParams, heightmap: &Heightmap) -> (Vec<f32>, Vec<f32>) {
+    let (temperature, winds) = generate_climate_maps(
+        params.seed,
+        params.width,
+        params.height,
+        &heightmap.data,
+        params.climate.global_temperature_offset,
+        params.climate.polar_amplification,
+        params.climate.climate_latitude_exponent,
+        params.sea_level,
+    );
+    let humidity = calculate_humidity(
+        params.width,
+        params.height,
+        &heightmap.data,
+        &winds,
+        params.sea_level,
+        params.climate.global_humidity_offset,
+    );
+    (temperature, humidity)
+}
+
+/// Этап провинций: семена (по весу или с релаксацией), рост, слияние мелких провинций и граф смежности
+fn run_provinces(
+    params: &WorldGenerationParams,
+    heightmap: &Heightmap,
+    biome_map: &BiomeMap,
+    temperature: &[f32],
+    water_type: &[WaterType],
+    river_map: &RiverMap,
+) -> (Vec<Province>, Vec<u32>, ProvinceGraph) {
+    let land_pixels = water_type.iter().filter(|&&t| t == WaterType::Land).count();
+    let (num_land, num_sea) = split_province_counts(
+        land_pixels,
+        water_type.len() - land_pixels,
+        params.terrain.total_provinces,
+        params.sea_province_scale,
+    );
+    let seeds = match params.seeding.mode {
+        SeedingMode::Weighted => generate_province_seeds(
+            heightmap,
+            biome_map,
+            water_type,
+            num_land,
+            num_sea,
+            params.seed,
+        ),
+        SeedingMode::Relaxed => generate_relaxed_seeds(
+            biome_map,
+            water_type,
+            temperature,
+            num_land,
+            num_sea,
+            params.seed,
+            params.seeding.lloyd_iterations,
+        ),
+    };
+    // Цели площади по умолчанию — средняя площадь провинции своей поверхности
+    let targets = params.area.targets(
+        land_pixels / num_land.max(1),
+        (water_type.len() - land_pixels) / num_sea.max(1),
+    );
+    let (mut provinces, mut pixel_to_id) = if params.area.balance {
+        generate_provinces_balanced(
+            heightmap,
+            biome_map,
+            water_type,
+            river_map,
+            &seeds,
+            &params.growth,
+            &targets,
+            params.area.throttle,
+        )
+    } else {
+        grow_provinces(
+            heightmap,
+            biome_map,
+            water_type,
+            river_map,
+            &seeds,
+            &params.growth,
+        )
+    };
+
+    // Идентификаторы уплотняются до 0..N: пиксели и список провинций согласованы
+    merge_small_provinces(
+        &mut provinces,
+        &mut pixel_to_id,
+        params.width,
+        params.height,
+        &params.merge,
+        true,
+    );
+    if params.area.balance {
+        balance_province_areas(
+            &mut provinces,
+            &mut pixel_to_id,
+            biome_map,
+            water_type,
+            &targets,
+            &params.merge,
+        );
+    }
+    let graph = build_province_graph_with_map(&provinces, &pixel_to_id, biome_map, river_map);
+    (provinces, pixel_to_id, graph)
+}
+
+/// Этап регионов: группировка провинций примерно в `num_regions` регионов
+fn run_regions(
+    params: &WorldGenerationParams,
+    provinces: &[Province],
+    graph: &ProvinceGraph,
+) -> Vec<Region> {
+    let target_region_size = provinces.len().div_ceil(params.num_regions.max(1));
+    group_provinces_into_regions(provinces, graph, target_region_size)
+}